@@ -24,3 +24,166 @@ test('works', (t) => {
 }
 `)
 })
+
+test('forceNullable', (t) => {
+  const types = `
+    type User = {
+      id: number,
+      tags: string[]
+    }`
+  const out = native.generateSchema(types, JSON.stringify({User: 0}), `{
+    "syntax": "typescript",
+    "tsx": true,
+    "decorators": false,
+    "dynamicImport": false
+  }`, JSON.stringify({ forceNullable: true }))
+
+  t.is(out, `type User {
+  id: Int
+  tags: [String]
+}
+`)
+})
+
+test('accepts objects', (t) => {
+  const types = `
+    type User = {
+      id: number,
+      tags: string[]
+    }`
+  const out = native.generateSchema(types, {User: 0}, {
+    syntax: "typescript",
+    tsx: true,
+    decorators: false,
+    dynamicImport: false
+  }, { forceNullable: true })
+
+  t.is(out, `type User {
+  id: Int
+  tags: [String]
+}
+`)
+})
+
+test('accepts kind names in the manifest', (t) => {
+  const types = `
+    type User = {
+      id: number
+    }`
+  const out = native.generateSchema(types, {User: "object"}, {
+    syntax: "typescript",
+    tsx: true,
+    decorators: false,
+    dynamicImport: false
+  })
+
+  t.is(out, `type User {
+  id: Int!
+}
+`)
+})
+
+test('accepts manifest entries with settings', (t) => {
+  const types = `
+    type Node = {
+      id: number
+    }
+    type User = {
+      id: number
+    }
+    type RootQuery = {
+      me: User
+    }`
+  const out = native.generateSchema(types, {
+    Node: "interface",
+    User: { kind: "object", extends: ["Node"], directives: ["key(fields: \"id\")"] },
+    RootQuery: { kind: 0, root: "query" },
+  }, {
+    syntax: "typescript",
+    tsx: true,
+    decorators: false,
+    dynamicImport: false
+  })
+
+  t.is(out, `schema {
+  query: RootQuery
+}
+interface Node {
+  id: Int!
+}
+type User implements Node @key(fields: "id") {
+  id: Int!
+}
+type RootQuery {
+  me: User!
+}
+`)
+})
+
+test('reports progress for each type', (t) => {
+  const types = `
+    type User = {
+      id: number
+    }
+    type Query = {
+      me: User
+    }`
+  const seen: string[] = []
+  native.generateSchema(types, {User: "object", Query: "object"}, {
+    syntax: "typescript",
+    tsx: true,
+    decorators: false,
+    dynamicImport: false
+  }, undefined, (name: string) => seen.push(name))
+
+  t.deepEqual(seen, ["User", "Query"])
+})
+
+test('reuses registered manifests', (t) => {
+  const parseOptions = {
+    syntax: "typescript",
+    tsx: true,
+    decorators: false,
+    dynamicImport: false
+  }
+  const id = native.registerManifest({User: "object"})
+  const first = native.generateSchemaWithManifest(`type User = { id: number }`, id, parseOptions)
+  const second = native.generateSchemaWithManifest(`type User = { id: number, name: string }`, id, parseOptions)
+
+  t.is(first, `type User {
+  id: Int!
+}
+`)
+  t.is(second, `type User {
+  id: Int!
+  name: String!
+}
+`)
+
+  t.true(native.unregisterManifest(id))
+  t.false(native.unregisterManifest(id))
+  t.throws(() => native.generateSchemaWithManifest(`type User = { id: number }`, id, parseOptions), {
+    message: `Unknown manifest id: ${id}`
+  })
+})
+
+test('accepts codegen options', (t) => {
+  const types = `
+    type Query = {
+      user(id: number): Promise<{ name: string }>
+    }`
+  const out = native.generateSchema(types, {Query: "object"}, {
+    syntax: "typescript",
+    tsx: true,
+    decorators: false,
+    dynamicImport: false
+  }, { forceNullable: true, outputSuffix: "Payload" })
+
+  t.is(out, `type UserPayload {
+  name: String
+}
+type Query {
+  user(id: Int!): UserPayload
+}
+`)
+})
@@ -0,0 +1,79 @@
+//! Benchmarks schema generation over a large generated module. The Node binding can't
+//! be linked into a benchmark, so run with `cargo bench --no-default-features`
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use tsgql::{generate_schema_with_comments, parse_ts_with_comments, GraphQLKind};
+
+/// Parse options of the Node binding
+const PARSE_OPTIONS: &str =
+    r#"{ "syntax": "typescript", "tsx": true, "decorators": false, "dynamicImport": false }"#;
+
+/// Number of object types in the generated module
+const TYPES: usize = 2000;
+
+/// Returns a module with `TYPES` objects, each with scalar, list and reference fields, a
+/// method with an inline input arg and an inline return type, a string literal union and
+/// JSDoc comments, so every name synthesizing path is exercised, along with its manifest
+fn large_module() -> (String, HashMap<String, GraphQLKind>) {
+    let mut src = String::new();
+    let mut manifest = HashMap::new();
+    for i in 0..TYPES {
+        let next = (i + 1) % TYPES;
+        writeln!(
+            src,
+            "/** Type number {i} */
+            type Type{i} = {{
+                /** Unique id of the type */
+                id: string;
+                count: number;
+                tags: string[];
+                status: \"active\" | \"archived\";
+                /**
+                 * The following type
+                 * @deprecated Use items
+                 */
+                next: Type{next} | null;
+                items{i}(args: {{ first: number; after?: string }}): Promise<{{ total: number; nodes: Type{next}[] }}>;
+            }}",
+            i = i,
+            next = next
+        )
+        .unwrap();
+        manifest.insert(format!("Type{}", i), GraphQLKind::Object);
+    }
+
+    src.push_str("type Query = {\n");
+    for i in 0..TYPES {
+        writeln!(src, "    type{i}(id: string): Promise<Type{i}>;", i = i).unwrap();
+    }
+    src.push_str("}\n");
+    manifest.insert("Query".to_string(), GraphQLKind::Object);
+
+    (src, manifest)
+}
+
+fn bench_generate_schema(c: &mut Criterion) {
+    let (src, manifest) = large_module();
+    let (prog, comments) = parse_ts_with_comments(&src, PARSE_OPTIONS).unwrap();
+    let module = prog.module().unwrap();
+
+    c.bench_function("generate_schema", |b| {
+        b.iter_batched(
+            || (module.clone(), manifest.clone()),
+            |(module, manifest)| {
+                generate_schema_with_comments(module, comments.clone(), manifest).unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = bench_generate_schema
+}
+criterion_main!(benches);
@@ -0,0 +1,2 @@
+type User = { id: string; name: string; karma: number; }
+type Query = { findUser: (args: { id?: string, name?: string, test: string[] }) => Promise<User[]>; }
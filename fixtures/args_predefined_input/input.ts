@@ -0,0 +1,3 @@
+type User = { id: string; name: string; karma: number; }
+type FindUserInput = { name: string, id?: string }
+type Query = { findUser: (args: { input: FindUserInput }) => Promise<User | null>; }
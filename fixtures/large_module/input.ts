@@ -0,0 +1,1402 @@
+/** Type number 0 */
+type Type0 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type1 | null;
+    items0(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type1[] }>;
+}
+/** Type number 1 */
+type Type1 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type2 | null;
+    items1(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type2[] }>;
+}
+/** Type number 2 */
+type Type2 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type3 | null;
+    items2(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type3[] }>;
+}
+/** Type number 3 */
+type Type3 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type4 | null;
+    items3(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type4[] }>;
+}
+/** Type number 4 */
+type Type4 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type5 | null;
+    items4(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type5[] }>;
+}
+/** Type number 5 */
+type Type5 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type6 | null;
+    items5(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type6[] }>;
+}
+/** Type number 6 */
+type Type6 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type7 | null;
+    items6(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type7[] }>;
+}
+/** Type number 7 */
+type Type7 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type8 | null;
+    items7(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type8[] }>;
+}
+/** Type number 8 */
+type Type8 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type9 | null;
+    items8(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type9[] }>;
+}
+/** Type number 9 */
+type Type9 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type10 | null;
+    items9(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type10[] }>;
+}
+/** Type number 10 */
+type Type10 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type11 | null;
+    items10(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type11[] }>;
+}
+/** Type number 11 */
+type Type11 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type12 | null;
+    items11(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type12[] }>;
+}
+/** Type number 12 */
+type Type12 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type13 | null;
+    items12(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type13[] }>;
+}
+/** Type number 13 */
+type Type13 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type14 | null;
+    items13(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type14[] }>;
+}
+/** Type number 14 */
+type Type14 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type15 | null;
+    items14(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type15[] }>;
+}
+/** Type number 15 */
+type Type15 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type16 | null;
+    items15(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type16[] }>;
+}
+/** Type number 16 */
+type Type16 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type17 | null;
+    items16(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type17[] }>;
+}
+/** Type number 17 */
+type Type17 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type18 | null;
+    items17(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type18[] }>;
+}
+/** Type number 18 */
+type Type18 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type19 | null;
+    items18(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type19[] }>;
+}
+/** Type number 19 */
+type Type19 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type20 | null;
+    items19(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type20[] }>;
+}
+/** Type number 20 */
+type Type20 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type21 | null;
+    items20(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type21[] }>;
+}
+/** Type number 21 */
+type Type21 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type22 | null;
+    items21(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type22[] }>;
+}
+/** Type number 22 */
+type Type22 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type23 | null;
+    items22(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type23[] }>;
+}
+/** Type number 23 */
+type Type23 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type24 | null;
+    items23(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type24[] }>;
+}
+/** Type number 24 */
+type Type24 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type25 | null;
+    items24(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type25[] }>;
+}
+/** Type number 25 */
+type Type25 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type26 | null;
+    items25(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type26[] }>;
+}
+/** Type number 26 */
+type Type26 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type27 | null;
+    items26(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type27[] }>;
+}
+/** Type number 27 */
+type Type27 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type28 | null;
+    items27(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type28[] }>;
+}
+/** Type number 28 */
+type Type28 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type29 | null;
+    items28(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type29[] }>;
+}
+/** Type number 29 */
+type Type29 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type30 | null;
+    items29(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type30[] }>;
+}
+/** Type number 30 */
+type Type30 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type31 | null;
+    items30(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type31[] }>;
+}
+/** Type number 31 */
+type Type31 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type32 | null;
+    items31(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type32[] }>;
+}
+/** Type number 32 */
+type Type32 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type33 | null;
+    items32(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type33[] }>;
+}
+/** Type number 33 */
+type Type33 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type34 | null;
+    items33(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type34[] }>;
+}
+/** Type number 34 */
+type Type34 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type35 | null;
+    items34(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type35[] }>;
+}
+/** Type number 35 */
+type Type35 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type36 | null;
+    items35(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type36[] }>;
+}
+/** Type number 36 */
+type Type36 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type37 | null;
+    items36(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type37[] }>;
+}
+/** Type number 37 */
+type Type37 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type38 | null;
+    items37(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type38[] }>;
+}
+/** Type number 38 */
+type Type38 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type39 | null;
+    items38(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type39[] }>;
+}
+/** Type number 39 */
+type Type39 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type40 | null;
+    items39(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type40[] }>;
+}
+/** Type number 40 */
+type Type40 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type41 | null;
+    items40(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type41[] }>;
+}
+/** Type number 41 */
+type Type41 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type42 | null;
+    items41(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type42[] }>;
+}
+/** Type number 42 */
+type Type42 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type43 | null;
+    items42(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type43[] }>;
+}
+/** Type number 43 */
+type Type43 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type44 | null;
+    items43(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type44[] }>;
+}
+/** Type number 44 */
+type Type44 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type45 | null;
+    items44(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type45[] }>;
+}
+/** Type number 45 */
+type Type45 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type46 | null;
+    items45(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type46[] }>;
+}
+/** Type number 46 */
+type Type46 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type47 | null;
+    items46(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type47[] }>;
+}
+/** Type number 47 */
+type Type47 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type48 | null;
+    items47(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type48[] }>;
+}
+/** Type number 48 */
+type Type48 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type49 | null;
+    items48(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type49[] }>;
+}
+/** Type number 49 */
+type Type49 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type50 | null;
+    items49(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type50[] }>;
+}
+/** Type number 50 */
+type Type50 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type51 | null;
+    items50(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type51[] }>;
+}
+/** Type number 51 */
+type Type51 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type52 | null;
+    items51(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type52[] }>;
+}
+/** Type number 52 */
+type Type52 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type53 | null;
+    items52(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type53[] }>;
+}
+/** Type number 53 */
+type Type53 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type54 | null;
+    items53(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type54[] }>;
+}
+/** Type number 54 */
+type Type54 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type55 | null;
+    items54(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type55[] }>;
+}
+/** Type number 55 */
+type Type55 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type56 | null;
+    items55(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type56[] }>;
+}
+/** Type number 56 */
+type Type56 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type57 | null;
+    items56(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type57[] }>;
+}
+/** Type number 57 */
+type Type57 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type58 | null;
+    items57(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type58[] }>;
+}
+/** Type number 58 */
+type Type58 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type59 | null;
+    items58(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type59[] }>;
+}
+/** Type number 59 */
+type Type59 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type60 | null;
+    items59(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type60[] }>;
+}
+/** Type number 60 */
+type Type60 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type61 | null;
+    items60(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type61[] }>;
+}
+/** Type number 61 */
+type Type61 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type62 | null;
+    items61(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type62[] }>;
+}
+/** Type number 62 */
+type Type62 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type63 | null;
+    items62(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type63[] }>;
+}
+/** Type number 63 */
+type Type63 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type64 | null;
+    items63(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type64[] }>;
+}
+/** Type number 64 */
+type Type64 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type65 | null;
+    items64(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type65[] }>;
+}
+/** Type number 65 */
+type Type65 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type66 | null;
+    items65(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type66[] }>;
+}
+/** Type number 66 */
+type Type66 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type67 | null;
+    items66(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type67[] }>;
+}
+/** Type number 67 */
+type Type67 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type68 | null;
+    items67(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type68[] }>;
+}
+/** Type number 68 */
+type Type68 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type69 | null;
+    items68(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type69[] }>;
+}
+/** Type number 69 */
+type Type69 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type70 | null;
+    items69(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type70[] }>;
+}
+/** Type number 70 */
+type Type70 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type71 | null;
+    items70(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type71[] }>;
+}
+/** Type number 71 */
+type Type71 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type72 | null;
+    items71(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type72[] }>;
+}
+/** Type number 72 */
+type Type72 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type73 | null;
+    items72(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type73[] }>;
+}
+/** Type number 73 */
+type Type73 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type74 | null;
+    items73(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type74[] }>;
+}
+/** Type number 74 */
+type Type74 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type75 | null;
+    items74(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type75[] }>;
+}
+/** Type number 75 */
+type Type75 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type76 | null;
+    items75(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type76[] }>;
+}
+/** Type number 76 */
+type Type76 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type77 | null;
+    items76(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type77[] }>;
+}
+/** Type number 77 */
+type Type77 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type78 | null;
+    items77(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type78[] }>;
+}
+/** Type number 78 */
+type Type78 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type79 | null;
+    items78(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type79[] }>;
+}
+/** Type number 79 */
+type Type79 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type80 | null;
+    items79(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type80[] }>;
+}
+/** Type number 80 */
+type Type80 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type81 | null;
+    items80(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type81[] }>;
+}
+/** Type number 81 */
+type Type81 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type82 | null;
+    items81(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type82[] }>;
+}
+/** Type number 82 */
+type Type82 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type83 | null;
+    items82(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type83[] }>;
+}
+/** Type number 83 */
+type Type83 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type84 | null;
+    items83(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type84[] }>;
+}
+/** Type number 84 */
+type Type84 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type85 | null;
+    items84(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type85[] }>;
+}
+/** Type number 85 */
+type Type85 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type86 | null;
+    items85(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type86[] }>;
+}
+/** Type number 86 */
+type Type86 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type87 | null;
+    items86(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type87[] }>;
+}
+/** Type number 87 */
+type Type87 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type88 | null;
+    items87(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type88[] }>;
+}
+/** Type number 88 */
+type Type88 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type89 | null;
+    items88(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type89[] }>;
+}
+/** Type number 89 */
+type Type89 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type90 | null;
+    items89(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type90[] }>;
+}
+/** Type number 90 */
+type Type90 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type91 | null;
+    items90(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type91[] }>;
+}
+/** Type number 91 */
+type Type91 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type92 | null;
+    items91(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type92[] }>;
+}
+/** Type number 92 */
+type Type92 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type93 | null;
+    items92(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type93[] }>;
+}
+/** Type number 93 */
+type Type93 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type94 | null;
+    items93(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type94[] }>;
+}
+/** Type number 94 */
+type Type94 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type95 | null;
+    items94(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type95[] }>;
+}
+/** Type number 95 */
+type Type95 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type96 | null;
+    items95(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type96[] }>;
+}
+/** Type number 96 */
+type Type96 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type97 | null;
+    items96(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type97[] }>;
+}
+/** Type number 97 */
+type Type97 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type98 | null;
+    items97(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type98[] }>;
+}
+/** Type number 98 */
+type Type98 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type99 | null;
+    items98(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type99[] }>;
+}
+/** Type number 99 */
+type Type99 = {
+    /** Unique id of the type */
+    id: string;
+    count: number;
+    tags: string[];
+    /**
+     * The following type
+     * @deprecated Use items
+     */
+    next: Type0 | null;
+    items99(args: { first: number; after?: string }): Promise<{ total: number; nodes: Type0[] }>;
+}
+type Query = {
+    type0(id: string): Promise<Type0>;
+    type1(id: string): Promise<Type1>;
+    type2(id: string): Promise<Type2>;
+    type3(id: string): Promise<Type3>;
+    type4(id: string): Promise<Type4>;
+    type5(id: string): Promise<Type5>;
+    type6(id: string): Promise<Type6>;
+    type7(id: string): Promise<Type7>;
+    type8(id: string): Promise<Type8>;
+    type9(id: string): Promise<Type9>;
+    type10(id: string): Promise<Type10>;
+    type11(id: string): Promise<Type11>;
+    type12(id: string): Promise<Type12>;
+    type13(id: string): Promise<Type13>;
+    type14(id: string): Promise<Type14>;
+    type15(id: string): Promise<Type15>;
+    type16(id: string): Promise<Type16>;
+    type17(id: string): Promise<Type17>;
+    type18(id: string): Promise<Type18>;
+    type19(id: string): Promise<Type19>;
+    type20(id: string): Promise<Type20>;
+    type21(id: string): Promise<Type21>;
+    type22(id: string): Promise<Type22>;
+    type23(id: string): Promise<Type23>;
+    type24(id: string): Promise<Type24>;
+    type25(id: string): Promise<Type25>;
+    type26(id: string): Promise<Type26>;
+    type27(id: string): Promise<Type27>;
+    type28(id: string): Promise<Type28>;
+    type29(id: string): Promise<Type29>;
+    type30(id: string): Promise<Type30>;
+    type31(id: string): Promise<Type31>;
+    type32(id: string): Promise<Type32>;
+    type33(id: string): Promise<Type33>;
+    type34(id: string): Promise<Type34>;
+    type35(id: string): Promise<Type35>;
+    type36(id: string): Promise<Type36>;
+    type37(id: string): Promise<Type37>;
+    type38(id: string): Promise<Type38>;
+    type39(id: string): Promise<Type39>;
+    type40(id: string): Promise<Type40>;
+    type41(id: string): Promise<Type41>;
+    type42(id: string): Promise<Type42>;
+    type43(id: string): Promise<Type43>;
+    type44(id: string): Promise<Type44>;
+    type45(id: string): Promise<Type45>;
+    type46(id: string): Promise<Type46>;
+    type47(id: string): Promise<Type47>;
+    type48(id: string): Promise<Type48>;
+    type49(id: string): Promise<Type49>;
+    type50(id: string): Promise<Type50>;
+    type51(id: string): Promise<Type51>;
+    type52(id: string): Promise<Type52>;
+    type53(id: string): Promise<Type53>;
+    type54(id: string): Promise<Type54>;
+    type55(id: string): Promise<Type55>;
+    type56(id: string): Promise<Type56>;
+    type57(id: string): Promise<Type57>;
+    type58(id: string): Promise<Type58>;
+    type59(id: string): Promise<Type59>;
+    type60(id: string): Promise<Type60>;
+    type61(id: string): Promise<Type61>;
+    type62(id: string): Promise<Type62>;
+    type63(id: string): Promise<Type63>;
+    type64(id: string): Promise<Type64>;
+    type65(id: string): Promise<Type65>;
+    type66(id: string): Promise<Type66>;
+    type67(id: string): Promise<Type67>;
+    type68(id: string): Promise<Type68>;
+    type69(id: string): Promise<Type69>;
+    type70(id: string): Promise<Type70>;
+    type71(id: string): Promise<Type71>;
+    type72(id: string): Promise<Type72>;
+    type73(id: string): Promise<Type73>;
+    type74(id: string): Promise<Type74>;
+    type75(id: string): Promise<Type75>;
+    type76(id: string): Promise<Type76>;
+    type77(id: string): Promise<Type77>;
+    type78(id: string): Promise<Type78>;
+    type79(id: string): Promise<Type79>;
+    type80(id: string): Promise<Type80>;
+    type81(id: string): Promise<Type81>;
+    type82(id: string): Promise<Type82>;
+    type83(id: string): Promise<Type83>;
+    type84(id: string): Promise<Type84>;
+    type85(id: string): Promise<Type85>;
+    type86(id: string): Promise<Type86>;
+    type87(id: string): Promise<Type87>;
+    type88(id: string): Promise<Type88>;
+    type89(id: string): Promise<Type89>;
+    type90(id: string): Promise<Type90>;
+    type91(id: string): Promise<Type91>;
+    type92(id: string): Promise<Type92>;
+    type93(id: string): Promise<Type93>;
+    type94(id: string): Promise<Type94>;
+    type95(id: string): Promise<Type95>;
+    type96(id: string): Promise<Type96>;
+    type97(id: string): Promise<Type97>;
+    type98(id: string): Promise<Type98>;
+    type99(id: string): Promise<Type99>;
+}
@@ -0,0 +1,2 @@
+type Role = "admin" | "member";
+type User = { id: string; role: Role; }
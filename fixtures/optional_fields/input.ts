@@ -0,0 +1,2 @@
+type User = { id?: string; name?: string; karma?: number; }
+type Player = { user: User; level?: number; }
@@ -31,20 +31,21 @@ const run = () => {
   if (process.argv.length > 2) {
     path = process.argv[2];
   }
-  const { tsconfigPath, schema, out } = readConfig(path)
+  const { tsconfigPath, schema, out, forceNullable } = readConfig(path)
 
   const reducer = createReducer({tsconfigPath, path: schema})
   const [reduced, manifest] = reducer.generate();
 
   const contents = native.generateSchema(
     reduced,
-    JSON.stringify(manifest),
-    `{
-            "syntax": "typescript",
-            "tsx": true,
-            "decorators": false,
-            "dynamicImport": false
-      }`,
+    manifest,
+    {
+      syntax: "typescript",
+      tsx: true,
+      decorators: false,
+      dynamicImport: false,
+    },
+    { forceNullable: !!forceNullable },
   );
 
   fs.writeFileSync(out, contents);
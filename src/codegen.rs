@@ -1,24 +1,913 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 use std::sync::Arc;
 
-use apollo_encoder::{Field, InputField, InputObjectDef, InputValue, ObjectDef, Schema, Type_};
-use swc::{config::ParseOptions, try_with_handler, Compiler};
-use swc_common::{FileName, FilePathMapping, SourceMap};
+use apollo_encoder::{
+    EnumDef, EnumValue, Field, InputField, InputObjectDef, InputValue, InterfaceDef, ObjectDef,
+    ScalarDef, Schema, SchemaDef, Type_, UnionDef,
+};
+use apollo_parser::ast::AstNode;
+use apollo_parser::{ast, Parser};
+use serde::{Deserialize, Serialize};
+use swc::config::{JscTarget, ParseOptions};
+use swc::{try_with_handler, Compiler, SwcComments};
+use swc_common::comments::{CommentKind, Comments};
+use swc_common::{BytePos, FileName, FilePathMapping, SourceMap, Span, Spanned, DUMMY_SP};
+use swc_ecmascript::ast::{
+    Accessibility, Class, ClassMember, ExportDecl, Invalid, KeyValueProp, MethodKind, ObjectLit,
+    ParenExpr, Pat, Program, Prop, PropName, PropOrSpread, TsConstAssertion, TsEnumMemberId,
+    TsFnOrConstructorType, TsFnType, TsModuleDecl, TsNamespaceBody, TsParenthesizedType,
+    TsQualifiedName,
+};
 use swc_ecmascript::ast::{
-    BindingIdent, Decl, Expr, Module, ModuleItem, Stmt, TsArrayType, TsEntityName, TsFnParam,
-    TsKeywordType, TsKeywordTypeKind, TsPropertySignature, TsType, TsTypeAnn, TsTypeElement,
-    TsTypeParamInstantiation, TsTypeRef, TsUnionOrIntersectionType, TsUnionType,
+    BindingIdent, Decl, Expr, ImportDecl, ImportDefaultSpecifier, ImportNamedSpecifier,
+    ImportSpecifier, Lit, Module, ModuleDecl, ModuleItem, Number, Stmt, Str, TsArrayType,
+    TsConditionalType, TsEntityName, TsFnParam, TsKeywordType, TsKeywordTypeKind, TsLit, TsLitType,
+    TsMethodSignature, TsPropertySignature, TsType, TsTypeAnn, TsTypeElement, TsTypeLit,
+    TsTypeOperator, TsTypeOperatorOp, TsTypeParamInstantiation, TsTypeQuery, TsTypeQueryExpr,
+    TsTypeRef, TsUnionOrIntersectionType, TsUnionType,
 };
-use swc_ecmascript::ast::{Program, TsFnOrConstructorType, TsFnType};
+use swc_ecmascript::parser::{EsConfig, Syntax, TsConfig};
+use swc_ecmascript::utils::DropSpan;
+use swc_ecmascript::visit::{Node, Visit, VisitMut, VisitMutWith, VisitWith};
 
 use anyhow::{Context, Result};
 
-pub fn generate_schema(prog: Module, manifest: HashMap<String, GraphQLKind>) -> Result<String> {
-    let mut ctx = CodeGenCtx::new(manifest);
+use crate::SourceError;
+
+/// Generates a GraphQL schema from `prog` with the default `CodeGenOptions`. The
+/// manifest maps type names to their `GraphQLKind`, or to a `ManifestEntry` with
+/// per-type settings
+pub fn generate_schema<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, CodeGenOptions::default());
+    ctx.parse(prog)?;
+    ctx.finish()
+}
+
+/// Like `generate_schema_with_comments`, but with every setting of the generator given
+/// by `options`
+pub fn generate_schema_with_options<K: Into<ManifestEntry>>(
+    prog: Module,
+    comments: SwcComments,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, options);
+    ctx.comments = comments;
+    ctx.parse(prog)?;
+    ctx.finish()
+}
+
+/// Parses `code` with `parse_config` and generates its schema in one call, reading
+/// JSDoc tags when `parse_config.comments` is set
+pub fn generate_schema_from_source<K: Into<ManifestEntry>>(
+    code: &str,
+    manifest: HashMap<String, K>,
+    parse_config: &TsParseConfig,
+) -> Result<String> {
+    generate_schema_from_source_with_options(
+        code,
+        manifest,
+        parse_config,
+        CodeGenOptions::default(),
+    )
+}
+
+/// Like `generate_schema_from_source`, but with every setting of the generator given by
+/// `options`
+pub fn generate_schema_from_source_with_options<K: Into<ManifestEntry>>(
+    code: &str,
+    manifest: HashMap<String, K>,
+    parse_config: &TsParseConfig,
+    options: CodeGenOptions,
+) -> Result<String> {
+    let (prog, comments) = parse_with_options(code, parse_config.clone().into())?;
+    let module = prog
+        .module()
+        .ok_or_else(|| anyhow::anyhow!("Expected a module, got a script"))?;
+    generate_schema_with_options(module, comments, manifest, options)
+}
+
+/// Like `generate_schema`, but reads JSDoc tags like `@gqlDirective` from `comments`,
+/// which should come from `parse_ts_with_comments`
+pub fn generate_schema_with_comments<K: Into<ManifestEntry>>(
+    prog: Module,
+    comments: SwcComments,
+    manifest: HashMap<String, K>,
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, CodeGenOptions::default());
+    ctx.comments = comments;
+    ctx.parse(prog)?;
+    ctx.finish()
+}
+
+/// Like `generate_schema_with_options`, but calls `on_type` with the name of each
+/// manifested type once its declaration is parsed, so callers can report progress
+pub fn generate_schema_with_progress<K: Into<ManifestEntry>>(
+    prog: Module,
+    comments: SwcComments,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+    on_type: impl FnMut(&str),
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, options);
+    ctx.comments = comments;
+    ctx.parse_with_progress(prog, on_type)?;
+    ctx.finish()
+}
+
+/// Like `generate_schema_with_progress`, but takes a manifest that is shared between
+/// calls, like the ones registered with the Node binding, without copying its entries
+pub fn generate_schema_with_shared_manifest(
+    prog: Module,
+    comments: SwcComments,
+    manifest: Arc<HashMap<String, ManifestEntry>>,
+    options: CodeGenOptions,
+    on_type: impl FnMut(&str),
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_shared_manifest(manifest, options);
+    ctx.comments = comments;
+    ctx.parse_with_progress(prog, on_type)?;
+    ctx.finish()
+}
+
+/// Like `generate_schema`, but also returns warnings about types that were skipped or
+/// manifested without a declaration
+pub fn generate_schema_with_diagnostics<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+) -> Result<(String, Vec<Diagnostic>)> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, options);
+    ctx.parse(prog)?;
+    ctx.finish_with_diagnostics()
+}
+
+/// Like `generate_schema`, but parses every module in `modules` into the same schema so
+/// types can reference definitions from other files
+pub fn generate_schema_multi<K: Into<ManifestEntry>>(
+    modules: Vec<Module>,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, options);
+    for prog in modules {
+        ctx.parse(prog)?;
+    }
+    ctx.finish()
+}
+
+/// Like `generate_schema`, but when `validate` is true the output is checked with
+/// `validate_schema` before it is returned
+pub fn generate_schema_with_validation<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+    validate: bool,
+) -> Result<String> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, options);
+    ctx.parse(prog)?;
+    let sdl = ctx.finish()?;
+    if validate {
+        validate_schema(&sdl)?;
+    }
+    Ok(sdl)
+}
+
+/// Keeps the output of every declaration between runs, so regenerating the schema of a
+/// module in watch mode only parses the declarations that changed since the last
+/// `update`
+#[derive(Default)]
+pub struct SchemaCache {
+    pub options: CodeGenOptions,
+    /// Hash of the manifest and imports the cached declarations were parsed with
+    context: u64,
+    /// Parsed declarations keyed by name, with the hash of their AST without spans
+    entries: HashMap<String, (u64, ParsedDecl)>,
+    /// Names of the declarations parsed by the last `update`, the rest were reused
+    rebuilt: Vec<String>,
+}
+
+impl SchemaCache {
+    pub fn new(options: CodeGenOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Generates the schema of `prog` like `generate_schema`, reusing the declarations
+    /// that are unchanged since the last call. Everything is parsed again when the
+    /// manifest or the imports change
+    pub fn update<K: Into<ManifestEntry>>(
+        &mut self,
+        prog: Module,
+        manifest: HashMap<String, K>,
+    ) -> Result<String> {
+        let mut ctx = CodeGenCtx::with_manifest(manifest, self.options.clone());
+
+        let items = ctx.flatten_module_items(prog.body);
+        ctx.record_type_keys(&items);
+        ctx.record_unions(&items)?;
+        ctx.record_generic_aliases(&items);
+        ctx.record_const_enums(&items);
+        let mut stmts = Vec::new();
+        for item in ctx.interfaces_first(items) {
+            match item {
+                ModuleItem::Stmt(stmt) => stmts.push(stmt),
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => ctx.record_import(import),
+                ModuleItem::ModuleDecl(_) => {}
+            }
+        }
+
+        let context = Self::context_hash(&ctx);
+        if context != self.context {
+            self.entries.clear();
+            self.context = context;
+        }
+
+        self.rebuilt.clear();
+        let mut entries = HashMap::with_capacity(self.entries.len());
+        for stmt in stmts {
+            let name = match Self::decl_name(&stmt) {
+                Some(name) => name,
+                None => {
+                    ctx.parse_statement(stmt)?;
+                    continue;
+                }
+            };
+
+            let hash = Self::content_hash(&stmt, &ctx);
+            let parsed = match self.entries.remove(&name) {
+                Some((cached, parsed)) if cached == hash => parsed,
+                _ => {
+                    let mut child = ctx.child();
+                    child.parse_statement(stmt)?;
+                    self.rebuilt.push(name.clone());
+                    child.into_parsed_decl()
+                }
+            };
+            ctx.absorb(parsed.clone())?;
+            entries.insert(name, (hash, parsed));
+        }
+        self.entries = entries;
+
+        ctx.finish()
+    }
+
+    /// Names of the declarations parsed by the last `update`, in declaration order
+    pub fn rebuilt(&self) -> &[String] {
+        &self.rebuilt
+    }
+
+    fn decl_name(stmt: &Stmt) -> Option<String> {
+        match stmt {
+            Stmt::Decl(Decl::TsTypeAlias(alias)) => Some(alias.id.sym.to_string()),
+            Stmt::Decl(Decl::TsInterface(interface)) => Some(interface.id.sym.to_string()),
+            Stmt::Decl(Decl::TsEnum(decl)) => Some(decl.id.sym.to_string()),
+            _ => None,
+        }
+    }
+
+    fn context_hash(ctx: &CodeGenCtx) -> u64 {
+        let mut manifest = ctx.manifest_entries.iter().collect::<Vec<_>>();
+        manifest.sort_unstable_by_key(|(name, _)| *name);
+        let mut imports = ctx.imports.iter().collect::<Vec<_>>();
+        imports.sort_unstable_by_key(|(name, _)| *name);
+
+        let mut hasher = DefaultHasher::new();
+        manifest.hash(&mut hasher);
+        imports.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Hashes `stmt` without its spans, so moving a declaration doesn't invalidate it.
+    /// Declarations with `keyof` fields also hash the member names of every object type,
+    /// since their enums are generated from them, those with `Pick` or `Omit` hash the
+    /// members themselves, and those instantiating generic aliases hash the aliases.
+    /// References to literal and nullable unions hash the union, which is inlined
+    fn content_hash(stmt: &Stmt, ctx: &CodeGenCtx) -> u64 {
+        let mut stmt = stmt.clone();
+        stmt.visit_mut_with(&mut DropSpan {
+            preserve_ctxt: false,
+        });
+        let mut hasher = DefaultHasher::new();
+        stmt.hash(&mut hasher);
+
+        let mut finder = KeyofFinder {
+            found: false,
+            type_refs: HashSet::new(),
+            names: HashSet::new(),
+        };
+        stmt.visit_with(&Invalid { span: DUMMY_SP }, &mut finder);
+        if finder.found {
+            let mut type_keys = ctx.type_keys.iter().collect::<Vec<_>>();
+            type_keys.sort_unstable_by_key(|(name, _)| *name);
+            type_keys.hash(&mut hasher);
+        }
+        if PICK_TYPES
+            .iter()
+            .any(|name| finder.type_refs.contains(*name))
+        {
+            let mut object_members = ctx.object_members.clone().into_iter().collect::<Vec<_>>();
+            object_members.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            for (name, mut members) in object_members {
+                members.visit_mut_with(&mut DropSpan {
+                    preserve_ctxt: false,
+                });
+                name.hash(&mut hasher);
+                members.hash(&mut hasher);
+            }
+        }
+        let mut generics = finder
+            .type_refs
+            .iter()
+            .filter_map(|name| Some((name, ctx.generic_aliases.get(name)?)))
+            .collect::<Vec<_>>();
+        generics.sort_unstable_by_key(|(name, _)| *name);
+        for (name, (params, body)) in generics {
+            let mut body = body.clone();
+            body.visit_mut_with(&mut DropSpan {
+                preserve_ctxt: false,
+            });
+            name.hash(&mut hasher);
+            params.hash(&mut hasher);
+            body.hash(&mut hasher);
+        }
+        let mut names = finder.names.iter().collect::<Vec<_>>();
+        names.sort_unstable();
+        for name in names {
+            if let Some(values) = ctx.literal_unions.get(name) {
+                name.hash(&mut hasher);
+                values.iter().for_each(|(value, _)| value.hash(&mut hasher));
+            }
+            ctx.nullable_unions.contains(name).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Finds `keyof` operators, and the names of the generic types referenced with type
+/// arguments like `Pick` or `Connection`, in a declaration
+struct KeyofFinder {
+    found: bool,
+    type_refs: HashSet<String>,
+    /// Names of every type referenced, with or without type arguments
+    names: HashSet<String>,
+}
+
+impl Visit for KeyofFinder {
+    fn visit_ts_type_operator_op(&mut self, op: &TsTypeOperatorOp, _parent: &dyn Node) {
+        self.found |= *op == TsTypeOperatorOp::KeyOf;
+    }
+
+    fn visit_ts_type_ref(&mut self, type_ref: &TsTypeRef, _parent: &dyn Node) {
+        if let TsEntityName::Ident(ident) = &type_ref.type_name {
+            self.names.insert(ident.sym.to_string());
+            if type_ref.type_params.is_some() {
+                self.type_refs.insert(ident.sym.to_string());
+            }
+        }
+        type_ref.visit_children_with(self);
+    }
+}
+
+/// Replaces references to the type parameters of a generic alias with its type arguments
+struct SubstituteTypeParams {
+    args: HashMap<String, TsType>,
+}
+
+impl VisitMut for SubstituteTypeParams {
+    fn visit_mut_ts_type(&mut self, ty: &mut TsType) {
+        if let TsType::TsTypeRef(TsTypeRef {
+            type_name: TsEntityName::Ident(ident),
+            type_params: None,
+            ..
+        }) = ty
+        {
+            if let Some(arg) = self.args.get(ident.sym.as_ref()) {
+                *ty = arg.clone();
+                return;
+            }
+        }
+        ty.visit_mut_children_with(self);
+    }
+}
+
+/// Re-parses generated SDL with `apollo-parser`, erroring on syntax errors and on
+/// types that are defined more than once
+pub fn validate_schema(sdl: &str) -> Result<()> {
+    let tree = Parser::new(sdl).parse();
+    if let Some(err) = tree.errors().next() {
+        return Err(anyhow::anyhow!(
+            "Generated schema is invalid: {} at {}",
+            err.message(),
+            err.index()
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for def in tree.document().definitions() {
+        let name = match def {
+            ast::Definition::ObjectTypeDefinition(def) => def.name(),
+            ast::Definition::InputObjectTypeDefinition(def) => def.name(),
+            ast::Definition::InterfaceTypeDefinition(def) => def.name(),
+            ast::Definition::EnumTypeDefinition(def) => def.name(),
+            ast::Definition::ScalarTypeDefinition(def) => def.name(),
+            ast::Definition::UnionTypeDefinition(def) => def.name(),
+            _ => None,
+        };
+        if let Some(name) = name {
+            let name = name.text().to_string();
+            if !seen.insert(name.clone()) {
+                return Err(anyhow::anyhow!(
+                    "Generated schema is invalid: type `{}` is defined more than once",
+                    name
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Built-in scalars, which are never prefixed
+const BUILTIN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// Prepends `prefix` to the names of the types defined and referenced in `sdl`, except
+/// built-in scalars and root types
+fn prefix_type_names(sdl: &str, prefix: &str) -> String {
+    let tree = Parser::new(sdl).parse();
+    let mut offsets = Vec::new();
+    for node in tree.document().syntax().descendants() {
+        let name = if let Some(ty) = ast::NamedType::cast(node.clone()) {
+            ty.name()
+        } else if let Some(def) = ast::ObjectTypeDefinition::cast(node.clone()) {
+            def.name()
+        } else if let Some(def) = ast::ObjectTypeExtension::cast(node.clone()) {
+            def.name()
+        } else if let Some(def) = ast::InterfaceTypeDefinition::cast(node.clone()) {
+            def.name()
+        } else if let Some(def) = ast::InputObjectTypeDefinition::cast(node.clone()) {
+            def.name()
+        } else if let Some(def) = ast::EnumTypeDefinition::cast(node.clone()) {
+            def.name()
+        } else if let Some(def) = ast::ScalarTypeDefinition::cast(node.clone()) {
+            def.name()
+        } else if let Some(def) = ast::UnionTypeDefinition::cast(node) {
+            def.name()
+        } else {
+            None
+        };
+        if let Some(name) = name {
+            let text = name.text();
+            if !BUILTIN_SCALARS.contains(&text.as_str()) && !ROOT_TYPES.contains(&text.as_str()) {
+                offsets.push(usize::from(name.syntax().text_range().start()));
+            }
+        }
+    }
+    offsets.sort_unstable();
+
+    let mut prefixed = String::with_capacity(sdl.len() + offsets.len() * prefix.len());
+    let mut last = 0;
+    for offset in offsets {
+        prefixed.push_str(&sdl[last..offset]);
+        prefixed.push_str(prefix);
+        last = offset;
+    }
+    prefixed.push_str(&sdl[last..]);
+    prefixed
+}
+
+/// Writes the directives of each type in `directives` into `sdl`. Type directives follow
+/// the name and implemented interfaces, field directives follow the type and default value
+fn attach_directives(sdl: &str, directives: &HashMap<String, Directives>) -> String {
+    if directives.is_empty() {
+        return sdl.to_string();
+    }
+    // Nodes may end with trailing whitespace, which directives go before
+    fn end(node: &ast::SyntaxNode) -> usize {
+        let text = node.text().to_string();
+        usize::from(node.text_range().start()) + text.trim_end().len()
+    }
+
+    let tree = Parser::new(sdl).parse();
+    let mut insertions: Vec<(usize, &Vec<String>)> = Vec::new();
+    for def in tree.document().definitions() {
+        let (name, type_end, fields) = match def {
+            ast::Definition::ObjectTypeDefinition(def) => (
+                def.name(),
+                def.implements_interfaces()
+                    .map(|i| end(i.syntax()))
+                    .or_else(|| def.name().map(|n| end(n.syntax()))),
+                def.fields_definition()
+                    .map(|f| {
+                        f.field_definitions()
+                            .filter_map(|f| Some((f.name()?, end(f.ty()?.syntax()))))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            // Extensions only have fields, the type directives are on the definition
+            ast::Definition::ObjectTypeExtension(def) => (
+                def.name(),
+                None,
+                def.fields_definition()
+                    .map(|f| {
+                        f.field_definitions()
+                            .filter_map(|f| Some((f.name()?, end(f.ty()?.syntax()))))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            ast::Definition::InterfaceTypeDefinition(def) => (
+                def.name(),
+                def.name().map(|n| end(n.syntax())),
+                def.fields_definition()
+                    .map(|f| {
+                        f.field_definitions()
+                            .filter_map(|f| Some((f.name()?, end(f.ty()?.syntax()))))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            ast::Definition::InputObjectTypeDefinition(def) => (
+                def.name(),
+                def.name().map(|n| end(n.syntax())),
+                def.input_fields_definition()
+                    .map(|f| {
+                        f.input_value_definitions()
+                            .filter_map(|f| {
+                                let end = match f.default_value() {
+                                    Some(default) => end(default.syntax()),
+                                    None => end(f.ty()?.syntax()),
+                                };
+                                Some((f.name()?, end))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            ast::Definition::UnionTypeDefinition(def) => {
+                (def.name(), def.name().map(|n| end(n.syntax())), Vec::new())
+            }
+            ast::Definition::EnumTypeDefinition(def) => {
+                (def.name(), def.name().map(|n| end(n.syntax())), Vec::new())
+            }
+            ast::Definition::ScalarTypeDefinition(def) => {
+                (def.name(), def.name().map(|n| end(n.syntax())), Vec::new())
+            }
+            _ => continue,
+        };
+        let type_directives = match name.and_then(|name| directives.get(&name.text().to_string())) {
+            Some(type_directives) => type_directives,
+            None => continue,
+        };
+        if let Some(type_end) = type_end {
+            insertions.push((type_end, &type_directives.on_type));
+        }
+        for (field, field_end) in fields {
+            let field = field.text().to_string();
+            if let Some((_, on_field)) = type_directives
+                .on_fields
+                .iter()
+                .find(|(name, _)| *name == field)
+            {
+                insertions.push((field_end, on_field));
+            }
+        }
+    }
+    insertions.sort_by_key(|(offset, _)| *offset);
+
+    let mut attached = String::with_capacity(sdl.len());
+    let mut last = 0;
+    for (offset, directives) in insertions {
+        attached.push_str(&sdl[last..offset]);
+        for directive in directives {
+            attached.push_str(" @");
+            attached.push_str(directive);
+        }
+        last = offset;
+    }
+    attached.push_str(&sdl[last..]);
+    attached
+}
+
+/// Like `generate_schema`, but returns the `apollo_encoder::Schema` before it is
+/// encoded, so callers can add their own definitions to it
+pub fn build_schema<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+) -> Result<Schema> {
+    build_schema_with_options(prog, manifest, CodeGenOptions::default())
+}
+
+/// Like `build_schema`, but with every setting of the generator given by `options`
+pub fn build_schema_with_options<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+) -> Result<Schema> {
+    let mut ctx = CodeGenCtx::with_manifest(manifest, options);
     ctx.parse(prog)?;
-    Ok(ctx.finish())
+    let (schema, extensions, directives) = ctx.into_schema()?;
+    if !extensions.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Types tagged with @gqlExtend can't be added to a Schema, use generate_schema instead"
+        ));
+    }
+    if !directives.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Directives can't be added to a Schema, use generate_schema instead"
+        ));
+    }
+    Ok(schema)
+}
+
+/// Formatting applied to the encoded SDL by `format_sdl`. The default matches the
+/// output of `apollo_encoder`
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level
+    pub indent: usize,
+    /// Separate top level definitions with a blank line
+    pub blank_line_between_definitions: bool,
+    /// End the output with a newline
+    pub trailing_newline: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            blank_line_between_definitions: false,
+            trailing_newline: true,
+        }
+    }
+}
+
+/// Normalizes the SDL emitted by `apollo_encoder` according to `opts`. Existing blank
+/// lines between definitions are dropped, the contents of block string descriptions are
+/// kept as is
+pub fn format_sdl(sdl: &str, opts: &FormatOptions) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    // True while inside a top level block string description
+    let mut in_block_string = false;
+    // True when the previous top level line describes the next definition
+    let mut after_description = false;
+
+    for line in sdl.lines() {
+        let content = line.trim_start_matches(' ');
+        let depth = line.len() - content.len();
+
+        if in_block_string {
+            if depth == 0 && content == "\"\"\"" {
+                in_block_string = false;
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if content.is_empty() {
+            continue;
+        }
+
+        if depth == 0 && content != "}" {
+            if opts.blank_line_between_definitions && !after_description && !lines.is_empty() {
+                lines.push(String::new());
+            }
+            in_block_string = content == "\"\"\"";
+            after_description = content.starts_with('"');
+            lines.push(content.to_string());
+            continue;
+        }
+
+        let indent = depth / 2 * opts.indent + depth % 2;
+        lines.push(format!("{}{}", " ".repeat(indent), content));
+    }
+
+    let mut out = lines.join("\n");
+    if opts.trailing_newline && !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// How a `null` or `undefined` member of a union affects the GraphQL type of a field.
+/// Optional properties (`field?: T`) are always nullable
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NullishPolicy {
+    /// The type becomes nullable
+    Nullable,
+    /// The member is dropped without affecting nullability
+    Ignore,
+    /// The member is rejected with an error
+    Reject,
+}
+
+/// How fields typed `Map<K, V>` or `ReadonlyMap<K, V>` are represented
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MapRepresentation {
+    /// A custom scalar with the given name, like `JSON`
+    Scalar(String),
+    /// A list of objects with a `key` and a `value` field, synthesized per field like
+    /// `UserScoresKeyValue` for `User.scores`, or per arg like `UsersInputTagsKeyValue`
+    /// for the `tags` arg of `users`
+    KeyValueList,
+}
+
+/// Order in which the args of a field are emitted
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArgOrder {
+    /// The order they are declared in
+    Source,
+    Alphabetical,
+    /// Non-null args without a default first, each group in source order
+    RequiredFirst,
+}
+
+/// Settings of the generator, the default matches the output of `generate_schema`.
+/// Deserializes from camelCase keys like `{ "forceNullable": true }`, missing keys
+/// keep their default
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CodeGenOptions {
+    /// Emit every object field as nullable regardless of its Typescript type. Args and
+    /// input fields still respect optionality
+    pub force_nullable: bool,
+    /// Error on constructs that are otherwise coerced into a lossy GraphQL type, like
+    /// template literals into `String` or `any` into `any_scalar`
+    pub strict: bool,
+    /// How `T | null` affects nullability
+    pub null_policy: NullishPolicy,
+    /// How `T | undefined` affects nullability
+    pub undefined_policy: NullishPolicy,
+    /// Name of the custom scalar `bigint` fields are mapped to
+    pub bigint_scalar: String,
+    /// Scalar used for `any`/`unknown` fields, these are rejected when this is `None`
+    pub any_scalar: Option<String>,
+    /// Scalar used for types with index signatures (`{ [key: string]: T }`), these are
+    /// rejected when this is `None`
+    pub index_signature_scalar: Option<String>,
+    /// Representation of `Map` fields, these are rejected when this is `None`
+    pub map_representation: Option<MapRepresentation>,
+    /// Type names that resolve directly to a scalar instead of a manifested type, keyed
+    /// by the Typescript name, in addition to `BUILTIN_SCALAR_ALIASES`
+    pub scalar_aliases: HashMap<String, String>,
+    /// Always append the param name to inputs generated from inline object args
+    pub always_suffix_input_names: bool,
+    /// Appended to the field name to name inputs synthesized from inline object args
+    pub input_suffix: String,
+    /// Appended to the field name to name objects synthesized from inline return types
+    pub output_suffix: String,
+    /// Generate enums from inline string literal unions, otherwise they become `String`
+    pub literal_unions_as_enums: bool,
+    /// Generate enums of field names from `keyof T`, otherwise it becomes `String`
+    pub keyof_as_enums: bool,
+    /// Generate a `{Name}Input` mirror of the properties of objects referenced by args
+    /// and inputs, which otherwise is an error
+    pub mirror_inputs: bool,
+    /// Emit `implements` for every interface whose fields an object structurally contains
+    pub auto_implements: bool,
+    /// Order of the args of each field
+    pub arg_order: ArgOrder,
+    /// Replace the characters that aren't allowed in GraphQL names of types, fields, args
+    /// and enum values with `_`, instead of erroring
+    pub sanitize_names: bool,
+    /// Joins the segments of qualified references like `Schema.User` with this separator
+    /// to look them up, otherwise only the last segment is used
+    pub qualified_name_separator: Option<String>,
+    /// Prepended by `finish` to the name of every type, except built-in scalars and root
+    /// types, so schemas of multiple services can be merged
+    pub type_prefix: Option<String>,
+    /// Lines written by `finish` before every definition, like
+    /// `extend schema @link(url: "https://specs.apollo.dev/federation/v2.0")`
+    pub prologue: Vec<String>,
+    /// Definitions of the custom directives attached with tags or the manifest, like
+    /// `directive @auth(role: Role) on FIELD_DEFINITION`, written by `finish` before the
+    /// types so the schema validates on its own
+    pub directive_definitions: Vec<String>,
+    /// Formatting applied to the SDL by `finish`, the encoder output is kept when `None`
+    pub format: Option<FormatOptions>,
+}
+
+impl Default for CodeGenOptions {
+    fn default() -> Self {
+        Self {
+            force_nullable: false,
+            strict: false,
+            null_policy: NullishPolicy::Nullable,
+            undefined_policy: NullishPolicy::Nullable,
+            bigint_scalar: "BigInt".into(),
+            any_scalar: None,
+            index_signature_scalar: None,
+            map_representation: None,
+            scalar_aliases: HashMap::new(),
+            always_suffix_input_names: false,
+            input_suffix: "Input".into(),
+            output_suffix: "Output".into(),
+            literal_unions_as_enums: true,
+            keyof_as_enums: true,
+            mirror_inputs: false,
+            auto_implements: false,
+            arg_order: ArgOrder::Source,
+            sanitize_names: false,
+            qualified_name_separator: None,
+            type_prefix: None,
+            prologue: Vec::new(),
+            directive_definitions: Vec::new(),
+            format: None,
+        }
+    }
+}
+
+/// Codegen options of the Node and wasm bindings, passed as an optional object or JSON
+/// string after the parse options
+#[cfg(any(feature = "node", feature = "wasm"))]
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GenerateOptions {
+    /// Settings of the generator, like `forceNullable` or `inputSuffix`
+    #[serde(flatten)]
+    pub codegen: CodeGenOptions,
+    /// Re-parse the generated SDL and error if it is invalid
+    #[serde(default)]
+    pub validate: bool,
+}
+
+/// A non-fatal problem found while generating a schema
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagnostic {
+    /// A type alias was skipped because it isn't in the manifest
+    SkippedType(String),
+    /// A manifest entry has no matching type alias
+    MissingDeclaration(String),
+    /// A manifest entry has no matching type alias, but one that only differs by case
+    /// was skipped, like `user` for `type User`. Holds the entry and the declared name
+    ManifestCaseMismatch(String, String),
+    /// The template literal type of a field was collapsed to `String`, losing its pattern
+    TemplateLiteral(String),
 }
 
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SkippedType(name) => write!(f, "skipped type {} (not in manifest)", name),
+            Self::MissingDeclaration(name) => {
+                write!(f, "manifest entry {} has no matching declaration", name)
+            }
+            Self::ManifestCaseMismatch(name, declared) => write!(
+                f,
+                "manifest entry {} has no matching declaration, did you mean {}?",
+                name, declared
+            ),
+            Self::TemplateLiteral(field) => write!(
+                f,
+                "template literal type of field {} collapsed to String",
+                field
+            ),
+        }
+    }
+}
+
+/// Generic types whose type parameter is the GraphQL type of a field, `Promise` for
+/// queries and mutations and the async iterables for subscriptions
+const ASYNC_WRAPPERS: [&str; 5] = [
+    "Promise",
+    "AsyncIterable",
+    "AsyncIterator",
+    "AsyncIterableIterator",
+    "AsyncGenerator",
+];
+
+/// Generic arrays, and collections that are serialized as arrays, which become lists of
+/// their type parameter like `T[]`
+const LIST_WRAPPERS: [&str; 4] = ["Array", "ReadonlyArray", "Set", "ReadonlySet"];
+
+/// Generic maps, represented according to `map_representation`
+const MAP_WRAPPERS: [&str; 2] = ["Map", "ReadonlyMap"];
+
+/// Utility types that derive an object type from the members of another one
+const PICK_TYPES: [&str; 2] = ["Pick", "Omit"];
+
+/// Root operation types, which can be declared as extensions with `@gqlExtend`
+const ROOT_TYPES: [&str; 3] = ["Query", "Mutation", "Subscription"];
+
+/// Type names that always resolve to the built-in GraphQL scalar of the same name, so
+/// users can declare e.g. `type Int = number` and type fields precisely
+const BUILTIN_SCALAR_ALIASES: [&str; 3] = ["Int", "Float", "ID"];
+
 #[derive(Clone, Debug)]
 enum FieldKind {
     Input,
@@ -40,16 +929,19 @@ pub enum KeyedGraphQLKind {
     Input(InputObjectDef),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum GraphQLKind {
     Object,
     Input,
     Enum,
+    Interface,
+    Union,
 }
 
 pub enum ComputeNameKind<'a> {
     Input(&'a str, usize),
     Output,
+    Enum,
 }
 
 impl GraphQLKind {
@@ -58,36 +950,237 @@ impl GraphQLKind {
             0 => Some(GraphQLKind::Object),
             1 => Some(GraphQLKind::Input),
             2 => Some(GraphQLKind::Enum),
+            3 => Some(GraphQLKind::Interface),
+            4 => Some(GraphQLKind::Union),
+            _ => None,
+        }
+    }
+
+    /// Parses the name of a kind, like `"object"` or `"Input"`, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "object" => Some(GraphQLKind::Object),
+            "input" => Some(GraphQLKind::Input),
+            "enum" => Some(GraphQLKind::Enum),
+            "interface" => Some(GraphQLKind::Interface),
+            "union" => Some(GraphQLKind::Union),
             _ => None,
         }
     }
 }
 
-#[derive(Clone, Debug)]
-enum ParsedField {
+impl std::str::FromStr for GraphQLKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_name(s).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid GraphQL kind, expected object, input, enum, interface or union: {}",
+                s
+            )
+        })
+    }
+}
+
+/// Root operation type a manifested object is the schema's entry point for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum RootOperation {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl RootOperation {
+    /// Parses the name of an operation, like `"query"`, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "query" => Some(RootOperation::Query),
+            "mutation" => Some(RootOperation::Mutation),
+            "subscription" => Some(RootOperation::Subscription),
+            _ => None,
+        }
+    }
+}
+
+/// A manifest entry with per-type settings. A bare `GraphQLKind` converts into an entry
+/// without any
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ManifestEntry {
+    pub kind: GraphQLKind,
+    /// Makes the object the root type of an operation, emitted in a `schema` definition
+    pub root: Option<RootOperation>,
+    /// Interfaces an object implements
+    pub extends: Vec<String>,
+    /// Directives attached to the type, written like `key(fields: "id")`
+    pub directives: Vec<String>,
+}
+
+impl From<GraphQLKind> for ManifestEntry {
+    fn from(kind: GraphQLKind) -> Self {
+        ManifestEntry {
+            kind,
+            root: None,
+            extends: Vec::new(),
+            directives: Vec::new(),
+        }
+    }
+}
+
+/// Value of a manifest entry as written in JSON, either a bare kind or an object with
+/// per-type settings like `{ kind: "object", root: "query", extends: ["Node"] }`
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ManifestValue {
+    Kind(ManifestKind),
+    Entry(ManifestOptions),
+}
+
+/// Per-type settings of a manifest entry, see `ManifestEntry`
+#[derive(Deserialize)]
+pub struct ManifestOptions {
+    pub kind: ManifestKind,
+    #[serde(default)]
+    pub root: Option<String>,
+    #[serde(default)]
+    pub extends: Vec<String>,
+    #[serde(default)]
+    pub directives: Vec<String>,
+}
+
+impl ManifestValue {
+    /// Converts the value of the entry of `type_name`, which names it in errors
+    pub fn into_entry(self, type_name: &str) -> Result<ManifestEntry> {
+        let options = match self {
+            ManifestValue::Kind(kind) => return Ok(kind.into_kind(type_name)?.into()),
+            ManifestValue::Entry(options) => options,
+        };
+        let root = match options.root {
+            Some(root) => match RootOperation::from_name(&root) {
+                Some(root) => Some(root),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "Invalid root operation for {}: {}",
+                        type_name,
+                        root
+                    ))
+                }
+            },
+            None => None,
+        };
+        Ok(ManifestEntry {
+            kind: options.kind.into_kind(type_name)?,
+            root,
+            extends: options.extends,
+            directives: options.directives,
+        })
+    }
+}
+
+/// Kind of a manifest entry, either its name like `"object"` or its numeric code
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ManifestKind {
+    Code(u8),
+    Name(String),
+}
+
+impl ManifestKind {
+    fn into_kind(self, type_name: &str) -> Result<GraphQLKind> {
+        let kind = match &self {
+            ManifestKind::Code(code) => GraphQLKind::from_u8(*code),
+            ManifestKind::Name(name) => GraphQLKind::from_name(name),
+        };
+        kind.ok_or_else(|| {
+            let kind = match self {
+                ManifestKind::Code(code) => code.to_string(),
+                ManifestKind::Name(name) => name,
+            };
+            anyhow::anyhow!("Invalid manifest kind for {}: {}", type_name, kind)
+        })
+    }
+}
+
+/// A field argument, with what is needed to order it
+struct ParsedArg {
+    name: String,
+    /// Non-null without a default
+    required: bool,
+    value: InputValue,
+}
+
+/// Name and type of an object field, kept to compare objects against interfaces
+#[derive(Clone, Debug, PartialEq)]
+struct FieldSignature {
+    name: String,
+    ty: Type_,
+}
+
+/// A field of an object or input, with the directives `apollo_encoder` can't attach to it
+#[derive(Clone, Debug, PartialEq)]
+struct ParsedField {
+    name: String,
+    def: FieldDef,
+    /// Directives from JSDoc tags like `@gqlDirective`, without the leading `@`
+    directives: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FieldDef {
     Input(InputField),
-    Object(Field),
+    Object(Field, FieldSignature),
 }
 
 impl ParsedField {
     pub fn input(self) -> Option<InputField> {
-        match self {
-            Self::Input(input) => Some(input),
-            Self::Object(_) => None,
+        match self.def {
+            FieldDef::Input(input) => Some(input),
+            FieldDef::Object(..) => None,
         }
     }
 
     pub fn object(self) -> Option<Field> {
-        match self {
-            Self::Input(_) => None,
-            Self::Object(f) => Some(f),
+        match self.def {
+            FieldDef::Input(_) => None,
+            FieldDef::Object(f, _) => Some(f),
+        }
+    }
+
+    pub fn description(&mut self, description: Option<String>) {
+        match &mut self.def {
+            FieldDef::Input(input) => input.description(description),
+            FieldDef::Object(f, _) => f.description(description),
+        }
+    }
+
+    /// Sets the default value of an input field, object fields have none
+    pub fn default(&mut self, default: Option<String>) {
+        if let FieldDef::Input(input) = &mut self.def {
+            input.default(default);
+        }
+    }
+
+    pub fn signature(&self) -> Option<&FieldSignature> {
+        match &self.def {
+            FieldDef::Input(_) => None,
+            FieldDef::Object(_, signature) => Some(signature),
         }
     }
 
     pub fn new(kind: FieldKind, name: String, type_: Type_) -> Self {
-        match kind {
-            FieldKind::Input => Self::Input(InputField::new(name, type_)),
-            FieldKind::Object => Self::Object(Field::new(name, type_)),
+        let def = match kind {
+            FieldKind::Input => FieldDef::Input(InputField::new(name.clone(), type_)),
+            FieldKind::Object => {
+                let signature = FieldSignature {
+                    name: name.clone(),
+                    ty: type_.clone(),
+                };
+                FieldDef::Object(Field::new(name.clone(), type_), signature)
+            }
+        };
+        Self {
+            name,
+            def,
+            directives: Vec::new(),
         }
     }
 
@@ -97,15 +1190,122 @@ impl ParsedField {
         type_: Type_,
         args: Vec<InputValue>,
     ) -> Option<Self> {
-        if let Self::Object(mut field) = Self::new(kind, name, type_) {
-            args.into_iter().for_each(|f| field.arg(f));
-            Some(Self::Object(field))
-        } else {
-            None
+        let mut field = Self::new(kind, name, type_);
+        match &mut field.def {
+            FieldDef::Object(def, _) => args.into_iter().for_each(|f| def.arg(f)),
+            FieldDef::Input(_) => return None,
+        }
+        Some(field)
+    }
+}
+
+/// Directives of a type and of its fields, without the leading `@`. `apollo_encoder`
+/// can't attach directives, so `attach_directives` writes them into the encoded SDL
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Directives {
+    on_type: Vec<String>,
+    /// Directives of each field, keyed by field name
+    on_fields: Vec<(String, Vec<String>)>,
+}
+
+impl Directives {
+    fn of_fields(fields: &[ParsedField]) -> Self {
+        Self {
+            on_type: Vec::new(),
+            on_fields: fields
+                .iter()
+                .filter(|f| !f.directives.is_empty())
+                .map(|f| (f.name.clone(), f.directives.clone()))
+                .collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.on_type.is_empty() && self.on_fields.is_empty()
+    }
+
+    /// Returns the SDL of the single type `name`, encoded as `sdl`, with these directives
+    fn attach(&self, name: &str, sdl: String) -> String {
+        if self.is_empty() {
+            return sdl;
+        }
+        let mut directives = HashMap::new();
+        directives.insert(name.to_string(), self.clone());
+        attach_directives(&sdl, &directives)
+    }
+}
+
+/// A definition waiting to be added to the schema. Manifested objects stay open until
+/// the schema is built, so later declarations of the same type can merge their fields in
+#[derive(Clone)]
+enum Definition {
+    ManifestObject {
+        name: String,
+        fields: Vec<ParsedField>,
+        /// Directives from JSDoc tags like `@gqlKey`, without the leading `@`
+        directives: Vec<String>,
+    },
+    /// Object synthesized from an inline object type
+    Object {
+        name: String,
+        description: Option<String>,
+        fields: Vec<ParsedField>,
+    },
+    Input {
+        name: String,
+        def: InputObjectDef,
+        directives: Directives,
+    },
+    Interface {
+        name: String,
+        def: InterfaceDef,
+        directives: Directives,
+    },
+    Union {
+        name: String,
+        def: UnionDef,
+    },
+    Enum {
+        name: String,
+        def: EnumDef,
+    },
+    Scalar {
+        name: String,
+    },
+    /// `extend type` of a root type, declared with `@gqlExtend`
+    Extension {
+        name: String,
+        fields: Vec<ParsedField>,
+    },
+}
+
+impl Definition {
+    fn name(&self) -> &str {
+        match self {
+            Self::ManifestObject { name, .. }
+            | Self::Object { name, .. }
+            | Self::Extension { name, .. }
+            | Self::Input { name, .. }
+            | Self::Interface { name, .. }
+            | Self::Union { name, .. }
+            | Self::Enum { name, .. }
+            | Self::Scalar { name } => name,
         }
     }
 }
 
+/// Everything parsing a single declaration added to a `CodeGenCtx`, which
+/// `SchemaCache` keeps to add it again without parsing the declaration
+#[derive(Clone)]
+struct ParsedDecl {
+    definitions: Vec<Definition>,
+    synthesized: HashMap<String, String>,
+    defined: HashMap<String, String>,
+    referenced: HashSet<String>,
+    interfaces: Vec<(String, Vec<FieldSignature>)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
 /// The main struct used for generating GraphQL schemas from a widened
 /// tsgql Typescript schema input.
 ///
@@ -113,32 +1313,160 @@ impl ParsedField {
 /// it to use SWC's Visitor trait + state variables.
 /// Some good examples are in the Next.js [repo](https://github.com/vercel/next.js/tree/canary/packages/next/build/swc/src)
 struct CodeGenCtx {
-    schema: Schema,
+    /// Definitions in the order they are emitted
+    definitions: Vec<Definition>,
+    /// Index in `definitions` of each manifested object, which later declarations of
+    /// the same type are merged into
+    object_indices: HashMap<String, usize>,
     manifest: HashMap<String, GraphQLKind>,
+    options: CodeGenOptions,
 
     /// True when we are parsing the inputs of a field with arguments
     parsing_inputs: bool,
     /// True when we are parsing the output of a field with arguments
     parsing_output: bool,
+    /// Description of the property or param being parsed, given to the types
+    /// synthesized from its inline objects
+    field_description: Option<String>,
+
+    /// Member names of the object types declared so far, keyed by type name
+    type_keys: HashMap<String, Vec<String>>,
+    /// Properties of the object types declared so far, keyed by type name, recorded when
+    /// `mirror_inputs` is set
+    object_properties: HashMap<String, TsType>,
+    /// Members of the object types declared so far, keyed by type name, which `Pick`
+    /// and `Omit` select from
+    object_members: HashMap<String, Vec<TsTypeElement>>,
+    /// Values of the string literal union aliases declared so far, keyed by type name,
+    /// which `Exclude` and `Extract` are resolved over
+    literal_unions: HashMap<String, Vec<(String, Span)>>,
+    /// Manifested union aliases that include `null` or `undefined`, references to them
+    /// are nullable
+    nullable_unions: HashSet<String>,
+    /// Type parameters and bodies of the generic aliases declared so far, keyed by type
+    /// name, which are emitted once per instantiation like `UserConnection`
+    generic_aliases: HashMap<String, (Vec<String>, TsType)>,
+    /// Manifested enums declared as `const Role = { ... } as const`, the type alias of the
+    /// same name that usually accompanies them is skipped
+    const_enums: HashSet<String>,
+    /// GraphQL names of the types renamed with a `@gqlName` tag, keyed by Typescript name
+    type_names: HashMap<String, String>,
+    /// Manifest entries with per-type settings, keyed by type name
+    manifest_entries: Arc<HashMap<String, ManifestEntry>>,
+    /// Custom scalars already emitted, so each `scalar` definition is only written once
+    declared_scalars: HashSet<String>,
+    /// SDL of the types synthesized from inline object literals, keyed by their name
+    synthesized: HashMap<String, String>,
+    /// SDL of the manifested inputs and interfaces that were emitted, keyed by their name.
+    /// Objects are merged instead of compared, so their SDL is left empty
+    defined: HashMap<String, String>,
+    /// Names of the manifested types referenced by fields and args
+    referenced: HashSet<String>,
+    /// Names and kinds of the types whose fields are being parsed, innermost last
+    parent_types: Vec<(String, FieldKind)>,
+    /// Field signatures of the interfaces emitted so far, in declaration order
+    interfaces: Vec<(String, Vec<FieldSignature>)>,
+    /// Names imported by the module being parsed, mapped to the name they were exported
+    /// under and the module specifier they were imported from
+    imports: HashMap<String, (String, String)>,
+    /// Comments of the module being parsed, used to read JSDoc tags
+    comments: SwcComments,
+    /// Lines of the JSDoc comments read so far, keyed by the position they lead. Every
+    /// field looks up its tags several times, so the comments are only split once
+    jsdoc: RefCell<HashMap<BytePos, Rc<Vec<String>>>>,
+    /// Warnings collected while parsing, returned by `finish_with_diagnostics`
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl CodeGenCtx {
     /// `manifest` is generated from the first pass in the Typescript compiler API code
-    fn new(manifest: HashMap<String, GraphQLKind>) -> Self {
-        let schema = Schema::new();
+    fn new(manifest: HashMap<String, GraphQLKind>, options: CodeGenOptions) -> Self {
         Self {
-            schema,
+            definitions: Vec::new(),
+            object_indices: HashMap::new(),
             manifest,
+            options,
             parsing_inputs: false,
             parsing_output: false,
+            field_description: None,
+            type_keys: HashMap::new(),
+            literal_unions: HashMap::new(),
+            nullable_unions: HashSet::new(),
+            generic_aliases: HashMap::new(),
+            const_enums: HashSet::new(),
+            object_properties: HashMap::new(),
+            object_members: HashMap::new(),
+            type_names: HashMap::new(),
+            manifest_entries: Arc::default(),
+            declared_scalars: HashSet::new(),
+            synthesized: HashMap::new(),
+            defined: HashMap::new(),
+            referenced: HashSet::new(),
+            parent_types: Vec::new(),
+            interfaces: Vec::new(),
+            imports: HashMap::new(),
+            comments: SwcComments::default(),
+            jsdoc: RefCell::new(HashMap::new()),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Like `new`, but keeps the per-type settings of the manifest entries
+    fn with_manifest<K: Into<ManifestEntry>>(
+        manifest: HashMap<String, K>,
+        options: CodeGenOptions,
+    ) -> Self {
+        let manifest_entries = manifest
+            .into_iter()
+            .map(|(name, entry)| (name, entry.into()))
+            .collect::<HashMap<String, ManifestEntry>>();
+        Self::with_shared_manifest(Arc::new(manifest_entries), options)
+    }
+
+    /// Like `with_manifest`, but only the kinds are copied out of the shared entries
+    fn with_shared_manifest(
+        manifest_entries: Arc<HashMap<String, ManifestEntry>>,
+        options: CodeGenOptions,
+    ) -> Self {
+        let kinds = manifest_entries
+            .iter()
+            .map(|(name, entry)| (name.clone(), entry.kind))
+            .collect();
+        Self {
+            manifest_entries,
+            ..Self::new(kinds, options)
         }
     }
 
     fn parse(&mut self, prog: Module) -> Result<()> {
-        for item in prog.body {
+        self.parse_with_progress(prog, |_| {})
+    }
+
+    /// Parses `prog`, calling `on_type` with the name of each manifested type declared in it
+    fn parse_with_progress(&mut self, prog: Module, mut on_type: impl FnMut(&str)) -> Result<()> {
+        // Imports are scoped to the module they appear in, and so are comment positions
+        self.imports.clear();
+        self.jsdoc.get_mut().clear();
+
+        let items = self.flatten_module_items(prog.body);
+        self.record_type_keys(&items);
+        self.record_unions(&items)?;
+        self.record_generic_aliases(&items);
+        self.record_const_enums(&items);
+        self.record_type_names(&items)?;
+        for item in self.interfaces_first(items) {
             match item {
                 ModuleItem::Stmt(stmt) => {
-                    self.parse_statement(stmt)?;
+                    let span = stmt.span();
+                    let declared = self.declared_type(&stmt);
+                    self.parse_statement(stmt)
+                        .map_err(|e| SourceError::wrap(span, e))?;
+                    if let Some(name) = declared {
+                        on_type(&name);
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                    self.record_import(import);
                 }
                 ModuleItem::ModuleDecl(_) => {}
             }
@@ -146,654 +1474,7179 @@ impl CodeGenCtx {
         Ok(())
     }
 
-    fn parse_statement(&mut self, stmt: Stmt) -> Result<()> {
-        match stmt {
-            Stmt::Decl(Decl::TsTypeAlias(alias)) => {
-                let ident = alias.id.sym.as_ref();
-                match self.manifest.get(ident) {
-                    Some(&GraphQLKind::Input) => {
-                        let mut input_def = InputObjectDef::new(ident.to_string());
-                        self.parse_typed_fields(FieldKind::Input, &alias.type_ann)?
-                            .into_iter()
-                            .for_each(|f| input_def.field(f.input().unwrap()));
-
-                        self.schema.input(input_def);
-                    }
-                    Some(_) => {
-                        let mut object_def = ObjectDef::new(ident.to_string());
-                        self.parse_typed_fields(FieldKind::Object, &alias.type_ann)?
-                            .into_iter()
-                            .for_each(|f| object_def.field(f.object().unwrap()));
-
-                        self.schema.object(object_def);
+    /// Returns the name of the manifested type declared by `stmt`, if any
+    fn declared_type(&self, stmt: &Stmt) -> Option<String> {
+        let ident = match stmt {
+            Stmt::Decl(Decl::TsTypeAlias(alias)) if alias.type_params.is_none() => &alias.id,
+            Stmt::Decl(Decl::TsInterface(interface)) => &interface.id,
+            Stmt::Decl(Decl::TsEnum(decl)) => &decl.id,
+            Stmt::Decl(Decl::Class(decl)) => &decl.ident,
+            Stmt::Decl(Decl::Var(var)) => {
+                return var.decls.iter().find_map(|decl| match &decl.name {
+                    Pat::Ident(BindingIdent { id, .. })
+                        if self.const_enums.contains(id.sym.as_ref()) =>
+                    {
+                        Some(self.graphql_type_name(id.sym.as_ref()))
                     }
-                    // Skip types not in the manifest
-                    None => {}
-                }
-                Ok(())
+                    _ => None,
+                })
             }
-            _ => todo!(),
+            _ => return None,
+        };
+        if self.manifest.contains_key(ident.sym.as_ref()) {
+            Some(self.graphql_type_name(ident.sym.as_ref()))
+        } else {
+            None
         }
     }
 
-    fn parse_typed_fields(
-        &mut self,
-        field_kind: FieldKind,
-        type_ann: &TsType,
-    ) -> Result<Vec<ParsedField>> {
-        let mut fields: Vec<ParsedField> = Vec::new();
-        match type_ann {
-            TsType::TsTypeLit(lit) => {
-                for member in &lit.members {
-                    match member {
-                        TsTypeElement::TsPropertySignature(prop_sig) => {
-                            fields.push(self.parse_field(field_kind.clone(), prop_sig)?);
+    /// Moves manifested interfaces to the front, so objects declared before them can
+    /// still implement them
+    fn interfaces_first(&self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+        let (interfaces, rest): (Vec<ModuleItem>, Vec<ModuleItem>) =
+            items.into_iter().partition(|item| {
+                let ident = match item {
+                    ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) => &alias.id,
+                    ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(interface))) => &interface.id,
+                    _ => return false,
+                };
+                matches!(
+                    self.manifest.get(ident.sym.as_ref()),
+                    Some(&GraphQLKind::Interface)
+                )
+            });
+        interfaces.into_iter().chain(rest).collect()
+    }
+
+    /// Unwraps `export` declarations and the bodies of `declare module`, `declare global`
+    /// and namespace blocks, so the type aliases in declaration files are reached
+    fn flatten_module_items(&self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+        let mut flattened = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { span, decl })) => {
+                    // Keep the JSDoc of `export type X` attached to the declaration itself
+                    self.comments.move_leading(span.lo, decl.span().lo);
+                    flattened
+                        .extend(self.flatten_module_items(vec![ModuleItem::Stmt(Stmt::Decl(decl))]))
+                }
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(TsModuleDecl {
+                    body: Some(mut body),
+                    ..
+                }))) => {
+                    // `namespace A.B {}` nests a declaration for each segment
+                    let block = loop {
+                        match body {
+                            TsNamespaceBody::TsModuleBlock(block) => break block,
+                            TsNamespaceBody::TsNamespaceDecl(decl) => body = *decl.body,
                         }
-                        r => return Err(anyhow::anyhow!("Invalid property type: {:?}", r)),
-                    }
+                    };
+                    flattened.extend(self.flatten_module_items(block.body));
                 }
+                item => flattened.push(item),
             }
-            r => todo!("Not implemented parsing in this context: {:?}", r),
-        };
-
-        Ok(fields)
+        }
+        flattened
     }
 
-    fn parse_field(
-        &mut self,
-        kind: FieldKind,
-        prop_sig: &TsPropertySignature,
-    ) -> Result<ParsedField> {
-        let key = match &*prop_sig.key {
-            Expr::Ident(ident) => ident.sym.to_string(),
-            _ => return Err(anyhow::anyhow!("Invalid property signature type")),
-        };
+    /// Returns the name `name` was exported under when it is imported, or `name` itself
+    fn resolve_import<'a>(&'a self, name: &'a str) -> &'a str {
+        match self.imports.get(name) {
+            Some((imported, _)) => imported,
+            None => name,
+        }
+    }
 
-        match self.parse_type(
-            &key,
-            &prop_sig.type_ann.as_ref().unwrap().type_ann,
-            prop_sig.optional,
-        )? {
-            (ty, None) => Ok(ParsedField::new(kind, key, ty)),
-            (ty, Some(args)) => match ParsedField::with_args(kind, key, ty, args) {
-                None => Err(anyhow::anyhow!(
-                    "Only ObjectDefs can contain input fields with args"
-                )),
-                Some(field) => Ok(field),
-            },
+    /// Records the names bound by an `import` declaration, so references to them can be
+    /// resolved to the type defined in the imported module
+    fn record_import(&mut self, import: ImportDecl) {
+        let src = import.src.value.to_string();
+        for specifier in import.specifiers {
+            match specifier {
+                ImportSpecifier::Named(ImportNamedSpecifier {
+                    local, imported, ..
+                }) => {
+                    let imported = imported.unwrap_or_else(|| local.clone());
+                    self.imports.insert(
+                        local.sym.to_string(),
+                        (imported.sym.to_string(), src.clone()),
+                    );
+                }
+                ImportSpecifier::Default(ImportDefaultSpecifier { local, .. }) => {
+                    self.imports
+                        .insert(local.sym.to_string(), (local.sym.to_string(), src.clone()));
+                }
+                // Namespace imports are referenced through qualified names, which aren't
+                // supported
+                ImportSpecifier::Namespace(_) => {}
+            }
         }
     }
 
-    /// Returns the type of a GraphQL field, returning arguments if it has any.
-    /// `field_name` is used to generate names for Inputs, and can be an empty string
-    /// if you don't expect the Typescript type to be a function
-    ///
-    /// Important thing to note is that we only consider a sub-set of types, because
-    /// the Typescript code is widened by the Typescript Compiler API before we receive it.
-    fn parse_type(
-        &mut self,
-        field_name: &str,
-        type_ann: &TsType,
-        optional: bool,
-    ) -> Result<(Type_, Option<Vec<InputValue>>)> {
-        let (ty, args) = match type_ann {
-            TsType::TsKeywordType(TsKeywordType { kind, .. }) => {
-                (Self::parse_keyword_type(kind)?, None)
+    fn parse_statement(&mut self, stmt: Stmt) -> Result<()> {
+        match stmt {
+            // Generic aliases are only emitted once instantiated
+            Stmt::Decl(Decl::TsTypeAlias(alias)) if alias.type_params.is_some() => Ok(()),
+            // `type Role = typeof Role[keyof typeof Role]` names the enum of the const object
+            Stmt::Decl(Decl::TsTypeAlias(alias))
+                if self.const_enums.contains(alias.id.sym.as_ref()) =>
+            {
+                Ok(())
             }
-            TsType::TsArrayType(TsArrayType { elem_type, .. }) => {
-                match (self.parsing_output, &**elem_type) {
-                    (true, TsType::TsTypeLit(_)) => {
-                        let name = Self::compute_new_name(ComputeNameKind::Output, field_name);
-                        self.parse_type_literal(FieldKind::Object, &name, elem_type)?;
-                        (
-                            Type_::List {
-                                ty: Box::new(Type_::NamedType { name }),
-                            },
-                            None,
-                        )
+            Stmt::Decl(Decl::TsTypeAlias(alias)) => {
+                self.parse_type_decl(alias.id.sym.as_ref(), alias.span, &alias.type_ann)
+            }
+            Stmt::Decl(Decl::TsInterface(interface)) => {
+                let ident = interface.id.sym.as_ref();
+                if !interface.extends.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Interfaces that extend other types are not supported, use a type alias instead (check: {})",
+                        ident
+                    ));
+                }
+
+                // An interface body is parsed like the equivalent object literal type
+                let type_ann = TsType::TsTypeLit(TsTypeLit {
+                    span: interface.body.span,
+                    members: interface.body.body,
+                });
+                self.parse_type_decl(ident, interface.span, &type_ann)
+            }
+            Stmt::Decl(Decl::TsEnum(decl)) => {
+                let ident = decl.id.sym.as_ref();
+                match self.manifest.get(ident) {
+                    Some(&GraphQLKind::Enum) => {
+                        let values = decl
+                            .members
+                            .iter()
+                            .map(|member| match &member.id {
+                                TsEnumMemberId::Ident(id) => (id.sym.to_string(), member.span),
+                                TsEnumMemberId::Str(Str { value, .. }) => {
+                                    (value.to_string(), member.span)
+                                }
+                            })
+                            .collect();
+                        let description = self.jsdoc_description(decl.span);
+                        let name = self.graphql_type_name(ident);
+                        self.declare_enum(&name, description, values)
                     }
-                    _ => {
-                        (
-                            Type_::List {
-                                // TODO: There is no way to set non-nullable array elements in TS,
-                                // meaning we cant represent [Int!]!
-                                ty: Box::new(self.parse_type(field_name, elem_type, true)?.0),
-                            },
-                            None,
-                        )
+                    Some(_) => Err(anyhow::anyhow!(
+                        "Typescript enums can only be manifested as enums (check: {})",
+                        ident
+                    )),
+                    None => {
+                        self.diagnostics
+                            .push(Diagnostic::SkippedType(ident.to_string()));
+                        Ok(())
                     }
                 }
             }
-            TsType::TsTypeRef(TsTypeRef {
-                type_name,
-                type_params,
-                ..
-            }) => self.parse_type_ref(field_name, type_name, type_params)?,
-            TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
-                params,
-                // `type_ann` here is return type
-                type_ann,
-                ..
-            })) => {
-                if params.len() != 1 {
-                    return Err(anyhow::anyhow!("Expected only one parameter for field arg"));
+            // Manifested classes are parsed like an interface with their public instance
+            // properties and methods
+            Stmt::Decl(Decl::Class(decl))
+                if self.manifest.contains_key(decl.ident.sym.as_ref()) =>
+            {
+                let ident = decl.ident.sym.as_ref();
+                if decl.class.super_class.is_some() {
+                    return Err(anyhow::anyhow!(
+                        "Classes that extend other classes are not supported (check: {})",
+                        ident
+                    ));
                 }
-
-                let input = &params[0];
-
-                let lit = match input {
-                    TsFnParam::Ident(BindingIdent { type_ann, .. }) => {
-                        if let Some(TsTypeAnn { type_ann, .. }) = type_ann {
-                            match **type_ann {
-                                TsType::TsTypeLit(ref lit) => Some(lit),
-                                _ => None,
-                            }
-                        } else {
-                            None
+                let span = decl.class.span;
+                let type_ann = Self::class_type(ident, decl.class)?;
+                self.parse_type_decl(ident, span, &type_ann)
+            }
+            // Values sharing the name of a manifested enum, like `const Role = { ... } as
+            // const`, emit an enum of their keys
+            Stmt::Decl(Decl::Var(var)) => {
+                for decl in var.decls {
+                    let (ident, object) = match (&decl.name, decl.init.as_deref()) {
+                        (Pat::Ident(BindingIdent { id, .. }), Some(init))
+                            if self.const_enums.contains(id.sym.as_ref()) =>
+                        {
+                            (id.sym.to_string(), Self::const_object(init))
                         }
-                    }
-                    _ => None,
-                };
+                        _ => continue,
+                    };
+                    let values = match object {
+                        Some(object) => Self::const_enum_values(&ident, object)?,
+                        None => continue,
+                    };
+                    let description = self.jsdoc_description(var.span);
+                    let name = self.graphql_type_name(&ident);
+                    self.declare_enum(&name, description, values)?;
+                }
+                Ok(())
+            }
+            // Functions, other variables and other classes in declaration files carry no
+            // schema types, and neither do other statements
+            _ => Ok(()),
+        }
+    }
 
-                let lit = match lit {
-                    None => return Err(anyhow::anyhow!("Type of Field args can only be objects")),
-                    Some(lit) => lit,
-                };
+    /// Returns the object literal of an `{ ... } as const` initializer
+    fn const_object(init: &Expr) -> Option<&ObjectLit> {
+        match init {
+            Expr::TsConstAssertion(TsConstAssertion { expr, .. }) => match &**expr {
+                Expr::Object(object) => Some(object),
+                Expr::Paren(ParenExpr { expr, .. }) => Self::const_object(expr),
+                _ => None,
+            },
+            Expr::Paren(ParenExpr { expr, .. }) => Self::const_object(expr),
+            _ => None,
+        }
+    }
 
-                let member_count = lit.members.len();
-                self.parsing_inputs = true;
-                let args = lit
-                    .members
-                    .iter()
-                    .map(|f| self.parse_arg_member(field_name, f, member_count))
-                    .collect::<Result<Vec<InputValue>>>()?;
-                self.parsing_inputs = false;
+    /// Returns the keys of a const object manifested as the enum `ident`, with the span
+    /// of each property so their JSDoc is read like enum members
+    fn const_enum_values(ident: &str, object: &ObjectLit) -> Result<Vec<(String, Span)>> {
+        object
+            .props
+            .iter()
+            .map(|prop| {
+                let key = match prop {
+                    PropOrSpread::Prop(prop) => match &**prop {
+                        Prop::KeyValue(KeyValueProp { key, .. }) => key,
+                        Prop::Shorthand(id) => return Ok((id.sym.to_string(), id.span)),
+                        _ => return Err(Self::const_enum_error(ident)),
+                    },
+                    PropOrSpread::Spread(_) => return Err(Self::const_enum_error(ident)),
+                };
+                match key {
+                    PropName::Ident(id) => Ok((id.sym.to_string(), prop.span())),
+                    PropName::Str(Str { value, .. }) => Ok((value.to_string(), prop.span())),
+                    _ => Err(Self::const_enum_error(ident)),
+                }
+            })
+            .collect()
+    }
 
-                self.parsing_output = true;
-                // Last param can be anything here, since we don't know if the return type is
-                // optional until we parse it. `self.parse_type()` will make sure to return
-                // the correct type if we are parsing return type
-                let (ret_ty, _) = self.parse_type(field_name, &type_ann.type_ann, true)?;
-                self.parsing_output = false;
+    fn const_enum_error(ident: &str) -> anyhow::Error {
+        anyhow::anyhow!(
+            "Const objects manifested as enums can only have properties named with identifiers or strings (check: {})",
+            ident
+        )
+    }
 
-                return Ok((ret_ty, Some(args)));
-            }
-            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(uni)) => {
-                let typ = Self::unwrap_union(uni)?;
-                return self.parse_type(field_name, typ, true);
-            }
-            // TODO: Move TsTypeLit in here
-            r => {
-                println!("{:?}", r);
-                todo!();
+    /// Records the manifested enums declared as `as const` object literals in `items`
+    fn record_const_enums(&mut self, items: &[ModuleItem]) {
+        for item in items {
+            if let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) = item {
+                for decl in &var.decls {
+                    if let (Pat::Ident(BindingIdent { id, .. }), Some(init)) =
+                        (&decl.name, &decl.init)
+                    {
+                        if Self::const_object(init).is_some()
+                            && self.manifest.get(id.sym.as_ref()) == Some(&GraphQLKind::Enum)
+                        {
+                            self.const_enums.insert(id.sym.to_string());
+                        }
+                    }
+                }
             }
-        };
-
-        if !optional {
-            return Ok((Type_::NonNull { ty: Box::new(ty) }, args));
         }
-
-        Ok((ty, args))
     }
 
-    fn parse_type_ref(
-        &mut self,
-        field_name: &str,
-        type_name: &TsEntityName,
-        type_params: &Option<TsTypeParamInstantiation>,
-    ) -> Result<(Type_, Option<Vec<InputValue>>)> {
-        if let TsEntityName::Ident(ident) = type_name {
-            if ident.sym.to_string() != "Promise" {
-                match self.manifest.get(ident.sym.as_ref()) {
-                    Some(&GraphQLKind::Object) if self.parsing_inputs => {
-                        return Err(anyhow::anyhow!(
-                            "Field args can only be Inputs (check: {})",
-                            ident.sym.as_ref()
-                        ));
+    /// Returns the object literal type with the public instance members of a class.
+    /// Getters become properties, and methods become fields with args
+    fn class_type(ident: &str, class: Class) -> Result<TsType> {
+        let mut members = Vec::new();
+        for member in class.body {
+            match member {
+                ClassMember::ClassProp(prop) => {
+                    if prop.is_static || Self::is_private(&prop.accessibility) {
+                        continue;
                     }
-                    Some(&GraphQLKind::Input) if !self.parsing_inputs => {
-                        return Err(anyhow::anyhow!(
-                            "Field type can't be an Input (check: {})",
-                            ident.sym.as_ref()
-                        ));
+                    members.push(TsTypeElement::TsPropertySignature(TsPropertySignature {
+                        span: prop.span,
+                        readonly: prop.readonly,
+                        key: prop.key,
+                        computed: prop.computed,
+                        optional: prop.is_optional,
+                        init: None,
+                        params: vec![],
+                        type_ann: prop.type_ann,
+                        type_params: None,
+                    }));
+                }
+                ClassMember::Method(method) => {
+                    if method.is_static
+                        || Self::is_private(&method.accessibility)
+                        || method.kind == MethodKind::Setter
+                    {
+                        continue;
                     }
-                    Some(_) => Ok((
-                        Type_::NamedType {
-                            name: ident.sym.to_string(),
-                        },
-                        None,
-                    )),
-                    None => return Err(anyhow::anyhow!("Undefined type: {}", ident.sym.as_ref())),
-                }
-            } else {
-                match type_params {
-                    None => Err(anyhow::anyhow!("Missing type parameter for Promise")),
-                    Some(TsTypeParamInstantiation { params, .. }) => {
-                        match params.len() {
-                            1 => {}
-                            other => {
-                                return Err(anyhow::anyhow!(
-                                    "Invalid amount of type parameters for Promise: {}",
-                                    other
-                                ))
-                            }
-                        }
-                        let typ = &params[0];
-
-                        // Somewhat confusing, but if we are here then we are parsing return of
-                        // a field with arguments, meaning we don't know the optionality of the
-                        // return type until we unwrap it from the Promise, meaning we should
-                        // discard the `optional` param and return here
-                        //
-                        // Maybe we should move this match branch into its own dedicated function,
-                        // and when we parse the return we call that instead of this function.
-                        match &**typ {
-                            TsType::TsUnionOrIntersectionType(
-                                TsUnionOrIntersectionType::TsUnionType(u),
-                            ) if Self::is_nullable_union(typ) => {
-                                let non_null = Self::unwrap_union(u)?;
-                                match non_null {
-                                    TsType::TsTypeLit(_) => {
-                                        let name = Self::compute_new_name(
-                                            ComputeNameKind::Output,
-                                            field_name,
-                                        );
-                                        self.parse_type_literal(
-                                            FieldKind::Object,
-                                            &name,
-                                            non_null,
-                                        )?;
-
-                                        Ok((Type_::NamedType { name }, None))
-                                    }
-                                    _ => self.parse_type(field_name, non_null, true),
-                                }
-                            }
-                            TsType::TsTypeLit(_) => {
-                                let name =
-                                    Self::compute_new_name(ComputeNameKind::Output, field_name);
-                                self.parse_type_literal(FieldKind::Object, &name, typ)?;
-                                Ok((
-                                    Type_::NonNull {
-                                        ty: Box::new(Type_::NamedType { name }),
-                                    },
-                                    None,
-                                ))
-                            }
-                            _ => self.parse_type("", typ, false),
+                    let key = match method.key {
+                        PropName::Ident(key) => Box::new(Expr::Ident(key)),
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "Class members must be named with identifiers (check: {})",
+                                ident
+                            ))
                         }
+                    };
+                    let function = method.function;
+                    if method.kind == MethodKind::Getter {
+                        members.push(TsTypeElement::TsPropertySignature(TsPropertySignature {
+                            span: method.span,
+                            readonly: true,
+                            key,
+                            computed: false,
+                            optional: method.is_optional,
+                            init: None,
+                            params: vec![],
+                            type_ann: function.return_type,
+                            type_params: None,
+                        }));
+                        continue;
                     }
+                    let params = function
+                        .params
+                        .into_iter()
+                        .map(|param| match param.pat {
+                            Pat::Ident(ident) => Ok(TsFnParam::Ident(ident)),
+                            _ => Err(anyhow::anyhow!(
+                                "Field args can only be declared with named parameters"
+                            )),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    members.push(TsTypeElement::TsMethodSignature(TsMethodSignature {
+                        span: method.span,
+                        readonly: false,
+                        key,
+                        computed: false,
+                        optional: method.is_optional,
+                        params,
+                        type_ann: function.return_type,
+                        type_params: function.type_params,
+                    }));
                 }
+                // Constructors, `#private` members and static blocks aren't part of the
+                // type of instances
+                _ => {}
             }
-        } else {
-            todo!()
         }
+        Ok(TsType::TsTypeLit(TsTypeLit {
+            span: class.span,
+            members,
+        }))
     }
 
-    fn parse_arg_member(
-        &mut self,
-        field_name: &str,
-        member: &TsTypeElement,
-        member_count: usize,
-    ) -> Result<InputValue> {
-        match member {
-            TsTypeElement::TsPropertySignature(prop_sig) => {
-                let ident = match &*prop_sig.key {
-                    Expr::Ident(ident) => ident,
-                    _ => todo!(),
-                };
+    fn is_private(accessibility: &Option<Accessibility>) -> bool {
+        matches!(
+            accessibility,
+            Some(Accessibility::Private) | Some(Accessibility::Protected)
+        )
+    }
 
-                let type_ann = match &prop_sig.type_ann {
-                    Some(t) => t,
-                    None => return Err(anyhow::anyhow!("Missing property")),
+    /// Emits the definition for the type alias or interface `ident` with the type `type_ann`
+    fn parse_type_decl(&mut self, ts_ident: &str, span: Span, type_ann: &TsType) -> Result<()> {
+        let ident = &self.graphql_type_name(ts_ident);
+        let ident = ident.as_str();
+        // `Pick` and `Omit` become the kind `ts_ident` is manifested as
+        let picked;
+        let type_ann = match self.pick_type(ident, type_ann)? {
+            Some(lit) => {
+                picked = lit;
+                &picked
+            }
+            None => type_ann,
+        };
+        match (
+            self.manifest.get(ts_ident),
+            self.index_signature_scalar(type_ann),
+        ) {
+            (Some(&GraphQLKind::Enum), _) => {
+                let values = match type_ann {
+                    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
+                        uni,
+                    )) => Self::string_literal_union(uni),
+                    _ => None,
+                };
+                let values = match values {
+                    Some(values) => values,
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Enums must be a union of string literals (check: {})",
+                            ident
+                        ))
+                    }
                 };
 
-                let name = ident.sym.as_ref();
+                let description = self.jsdoc_description(span);
+                self.declare_enum(ident, description, values)?;
+            }
+            // Types with index signatures can't be represented as objects, so they map
+            // to the configured scalar, like inline fields do
+            (Some(_), Some(scalar)) => {
+                self.check_coercion(
+                    &format!("Type `{}`", ident),
+                    "an index signature type",
+                    &scalar,
+                )?;
+                self.declare_scalar(&scalar);
+            }
+            (Some(&GraphQLKind::Interface), _) => {
+                let mut interface_def = InterfaceDef::new(ident.to_string());
+                let fields = self.parse_typed_fields(FieldKind::Object, ident, type_ann)?;
+                let signatures = fields
+                    .iter()
+                    .filter_map(|f| f.signature().cloned())
+                    .collect::<Vec<FieldSignature>>();
+                let directives = Directives {
+                    on_type: self.key_directives(ident, span)?,
+                    ..Directives::of_fields(&fields)
+                };
+                fields
+                    .into_iter()
+                    .for_each(|f| interface_def.field(f.object().unwrap()));
 
-                let type_ = match &*type_ann.type_ann {
-                    TsType::TsTypeLit(_) => {
-                        let input_name = Self::compute_new_name(
-                            ComputeNameKind::Input(name, member_count),
-                            field_name,
-                        );
-                        self.parse_arg_type_literal(
-                            &input_name,
-                            &type_ann.type_ann,
-                            prop_sig.optional,
-                        )?
+                let sdl = directives.attach(ident, interface_def.to_string());
+                if self.register_defined(ident, sdl)? {
+                    self.definitions.push(Definition::Interface {
+                        name: ident.to_string(),
+                        def: interface_def,
+                        directives,
+                    });
+                    self.interfaces.push((ident.to_string(), signatures));
+                }
+            }
+            (Some(&GraphQLKind::Union), _) => {
+                let members = match type_ann {
+                    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
+                        uni,
+                    )) => self.union_members(ident, uni)?,
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Unions must be a union of object types (check: {})",
+                            ident
+                        ))
                     }
+                };
+                let mut union_def = UnionDef::new(ident.to_string());
+                union_def.description(self.jsdoc_description(span));
+                members
+                    .into_iter()
+                    .for_each(|member| union_def.member(member));
+
+                if self.register_defined(ident, union_def.to_string())? {
+                    self.definitions.push(Definition::Union {
+                        name: ident.to_string(),
+                        def: union_def,
+                    });
+                }
+            }
+            (Some(&GraphQLKind::Input), _) => {
+                let one_of;
+                let (type_ann, on_type) = match type_ann {
                     TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
                         uni,
                     )) => {
-                        if !Self::is_nullable_union(&*type_ann.type_ann) {
-                            return Err(anyhow::anyhow!("Unions as field args must be nullable"));
-                        }
-                        let unwrapped = Self::unwrap_union(uni)?;
-                        match unwrapped {
-                            TsType::TsTypeLit(_) => {
-                                let input_name = Self::compute_new_name(
-                                    ComputeNameKind::Input(name, member_count),
-                                    field_name,
-                                );
-                                self.parse_arg_type_literal(&input_name, unwrapped, true)?
-                            }
-                            _ => {
-                                let (ty, _) = self.parse_type(name, unwrapped, true)?;
-                                ty
-                            }
-                        }
-                    }
-                    ty => {
-                        let (ty, _) = self.parse_type(name, ty, prop_sig.optional)?;
-                        ty
+                        one_of = Self::one_of_fields(ident, uni)?;
+                        (&one_of, vec!["oneOf".to_string()])
                     }
+                    type_ann => (type_ann, Vec::new()),
+                };
+                let mut input_def = InputObjectDef::new(ident.to_string());
+                let fields = self.parse_typed_fields(FieldKind::Input, ident, type_ann)?;
+                let directives = Directives {
+                    on_type,
+                    ..Directives::of_fields(&fields)
                 };
+                fields
+                    .into_iter()
+                    .for_each(|f| input_def.field(f.input().unwrap()));
 
-                Ok(InputValue::new(ident.sym.to_string(), type_))
+                let sdl = directives.attach(ident, input_def.to_string());
+                if self.register_defined(ident, sdl)? {
+                    self.definitions.push(Definition::Input {
+                        name: ident.to_string(),
+                        def: input_def,
+                        directives,
+                    });
+                }
             }
-            _ => Err(anyhow::anyhow!(
-                "Field args input can only contain properties"
-            )),
+            (Some(_), _)
+                if self
+                    .jsdoc_tags(span)
+                    .iter()
+                    .any(|(tag, _)| tag == "gqlExtend") =>
+            {
+                if !ROOT_TYPES.contains(&ident) {
+                    return Err(anyhow::anyhow!(
+                        "Only Query, Mutation and Subscription can be tagged with @gqlExtend (check: {})",
+                        ident
+                    ));
+                }
+                let fields = self.parse_typed_fields(FieldKind::Object, ident, type_ann)?;
+                self.definitions.push(Definition::Extension {
+                    name: ident.to_string(),
+                    fields,
+                });
+            }
+            (Some(_), _) => {
+                let directives = self.key_directives(ident, span)?;
+                let fields = self.parse_typed_fields(FieldKind::Object, ident, type_ann)?;
+                self.define_object(ident, fields, directives)?;
+            }
+            // Skip types not in the manifest
+            (None, _) => self
+                .diagnostics
+                .push(Diagnostic::SkippedType(ts_ident.to_string())),
         }
+        Ok(())
     }
 
-    fn parse_arg_type_literal(&mut self, name: &str, ty: &TsType, optional: bool) -> Result<Type_> {
-        self.parse_type_literal(FieldKind::Input, name, ty)?;
-
-        if !optional {
-            Ok(Type_::NonNull {
-                ty: Box::new(Type_::NamedType {
-                    name: name.to_string(),
-                }),
-            })
-        } else {
-            Ok(Type_::NamedType {
-                name: name.to_string(),
-            })
+    /// Returns the names of the members of the union `ident`, which must all be
+    /// manifested objects. `null` and `undefined` members only make references nullable
+    fn union_members(&mut self, ident: &str, uni: &TsUnionType) -> Result<Vec<String>> {
+        let mut members = Vec::new();
+        for ty in uni.types.iter().filter(|ty| !Self::is_nullish(ty)) {
+            let member = match Self::unparenthesized(ty) {
+                TsType::TsTypeRef(TsTypeRef {
+                    type_name: TsEntityName::Ident(member),
+                    type_params: None,
+                    ..
+                }) => self.resolve_import(&member.sym).to_string(),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Members of union {} must be references to object types",
+                        ident
+                    ))
+                }
+            };
+            if self.manifest.get(&member) != Some(&GraphQLKind::Object) {
+                return Err(anyhow::anyhow!(
+                    "Members of union {} must be manifested object types (check: {})",
+                    ident,
+                    member
+                ));
+            }
+            let member = self.graphql_type_name(&member);
+            self.reference(&member);
+            members.push(member);
         }
+        Ok(members)
     }
 
-    fn parse_type_literal(&mut self, kind: FieldKind, new_name: &str, ty: &TsType) -> Result<()> {
-        match kind {
-            FieldKind::Input => {
-                let mut input_def = InputObjectDef::new(new_name.into());
+    /// Combines an input declared as a union of single field objects, like
+    /// `{ byId: string } | { byName: string }`, into an object literal with each field
+    /// made optional, which becomes a `@oneOf` input
+    fn one_of_fields(ident: &str, uni: &TsUnionType) -> Result<TsType> {
+        let mut members = Vec::with_capacity(uni.types.len());
+        for ty in &uni.types {
+            match Self::unparenthesized(ty) {
+                TsType::TsTypeLit(TsTypeLit { members: lit, .. }) => match lit.as_slice() {
+                    [TsTypeElement::TsPropertySignature(prop_sig)] => {
+                        members.push(TsTypeElement::TsPropertySignature(TsPropertySignature {
+                            optional: true,
+                            ..prop_sig.clone()
+                        }))
+                    }
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Members of a @oneOf input must have exactly one field (check: {})",
+                            ident
+                        ))
+                    }
+                },
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Input unions can only contain object literals (check: {})",
+                        ident
+                    ))
+                }
+            }
+        }
 
-                self.parse_typed_fields(FieldKind::Input, ty)?
-                    .into_iter()
-                    .for_each(|f| input_def.field(f.input().unwrap()));
+        Ok(TsType::TsTypeLit(TsTypeLit {
+            span: uni.span,
+            members,
+        }))
+    }
 
-                self.schema.input(input_def);
+    fn parse_typed_fields(
+        &mut self,
+        field_kind: FieldKind,
+        type_name: &str,
+        type_ann: &TsType,
+    ) -> Result<Vec<ParsedField>> {
+        // The fields of an inline arg or return type are parsed like those of any other
+        // type, so inline objects nested in them are named after their path, like
+        // `FindUserOutputAddress`, instead of just their field
+        let parsing_inputs = std::mem::replace(&mut self.parsing_inputs, false);
+        let parsing_output = std::mem::replace(&mut self.parsing_output, false);
+        self.parent_types
+            .push((type_name.to_string(), field_kind.clone()));
+        let fields = self.parse_members(field_kind, type_name, type_ann);
+        self.parent_types.pop();
+        self.parsing_inputs = parsing_inputs;
+        self.parsing_output = parsing_output;
+        fields
+    }
 
-                Ok(())
-            }
-            FieldKind::Object => {
-                let mut object_def = ObjectDef::new(new_name.into());
+    fn parse_members(
+        &mut self,
+        field_kind: FieldKind,
+        type_name: &str,
+        type_ann: &TsType,
+    ) -> Result<Vec<ParsedField>> {
+        let mut fields: Vec<ParsedField> = Vec::new();
+        match type_ann {
+            TsType::TsTypeLit(lit) => {
+                // Checked on the emitted names, which `@gqlName` and `sanitize_names` may
+                // make collide when the keys don't
+                let mut names = HashSet::new();
+                for member in &lit.members {
+                    if self.is_ignored_member(member) {
+                        continue;
+                    }
 
-                self.parse_typed_fields(FieldKind::Object, ty)?
-                    .into_iter()
-                    .for_each(|f| object_def.field(f.object().unwrap()));
+                    match member {
+                        TsTypeElement::TsPropertySignature(prop_sig) => {
+                            fields.push(
+                                self.parse_field(field_kind.clone(), prop_sig)
+                                    .map_err(|e| SourceError::wrap(prop_sig.span, e))?,
+                            );
+                        }
+                        TsTypeElement::TsMethodSignature(method) => {
+                            fields.push(
+                                self.parse_method(field_kind.clone(), method)
+                                    .map_err(|e| SourceError::wrap(method.span, e))?,
+                            );
+                        }
+                        TsTypeElement::TsIndexSignature(_) => {
+                            return Err(anyhow::anyhow!(
+                                "Index signatures are not supported (check: {}), set index_signature_scalar to map these types to a scalar",
+                                type_name
+                            ))
+                        }
+                        r => return Err(anyhow::anyhow!("Invalid property type: {:?}", r)),
+                    }
+                    let name = &fields[fields.len() - 1].name;
+                    if !names.insert(name.clone()) {
+                        return Err(anyhow::anyhow!(
+                            "Duplicate field in type {}: {}",
+                            type_name,
+                            name
+                        ));
+                    }
+                }
 
-                self.schema.object(object_def);
+                if fields.is_empty() && !lit.members.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Every field of type {} is ignored with @gqlIgnore, types must have at least one field",
+                        type_name
+                    ));
+                }
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Type {} must be an object type, got {}",
+                    type_name,
+                    Self::describe_type(type_ann)
+                ))
+            }
+        };
 
-                Ok(())
+        Ok(fields)
+    }
+
+    fn parse_field(
+        &mut self,
+        kind: FieldKind,
+        prop_sig: &TsPropertySignature,
+    ) -> Result<ParsedField> {
+        let type_name = self.parent_type_name();
+        let key = Self::member_key(&type_name, &prop_sig.key, prop_sig.computed)?;
+        let type_ann = match &prop_sig.type_ann {
+            Some(type_ann) => type_ann,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Field `{}` of type {} has no type annotation",
+                    key,
+                    type_name
+                ));
             }
+        };
+
+        let (ty, args) = self.with_field_description(prop_sig.span, |ctx| {
+            ctx.parse_type(&key, &type_ann.type_ann, prop_sig.optional)
+        })?;
+        let ty = self.apply_id_tag(&key, prop_sig.span, ty)?;
+        let ty = self.apply_nullability_tag(&key, prop_sig.span, ty)?;
+        let mut directives = self.parse_directives(&key, prop_sig.span)?;
+        directives.extend(self.federation_directives(&kind, &key, prop_sig.span)?);
+        let name = self.jsdoc_name(&key, prop_sig.span)?.unwrap_or(key);
+        let name = self.legal_name(&type_name, name)?;
+
+        let mut field = self.new_parsed_field(kind, name, ty, args, directives)?;
+        field.description(self.jsdoc_description(prop_sig.span));
+        field.default(self.jsdoc_default(prop_sig.span));
+        Ok(field)
+    }
+
+    /// Returns the name of the type whose members are being parsed
+    fn parent_type_name(&self) -> String {
+        match self.parent_types.last() {
+            Some((type_name, _)) => type_name.clone(),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the name of a member of `type_name` declared with `key`, which must be an
+    /// identifier or a string literal like `"created-at"`
+    fn member_key(type_name: &str, key: &Expr, computed: bool) -> Result<String> {
+        match key {
+            _ if computed => Err(anyhow::anyhow!(
+                "Computed keys like `[Symbol.iterator]` are not supported, fields must be named with identifiers or string literals (check: {})",
+                type_name
+            )),
+            Expr::Ident(ident) => Ok(ident.sym.to_string()),
+            Expr::Lit(Lit::Str(Str { value, .. })) => Ok(value.to_string()),
+            _ => Err(anyhow::anyhow!(
+                "Fields must be named with identifiers or string literals (check: {})",
+                type_name
+            )),
         }
     }
 
-    fn finish(self) -> String {
-        self.schema.finish()
+    /// Returns `name` if it is a valid GraphQL name, which string literal keys, literal
+    /// union values and `$` in identifiers may not be. Otherwise errors with `owner`, or
+    /// returns it with `sanitize_name` when `sanitize_names` is set
+    fn legal_name(&self, owner: &str, name: String) -> Result<String> {
+        if Self::is_valid_name(&name) {
+            Ok(name)
+        } else if self.options.sanitize_names {
+            Ok(Self::sanitize_name(&name))
+        } else {
+            Err(anyhow::anyhow!(
+                "`{}` is not a valid GraphQL name (check: {})",
+                name,
+                owner
+            ))
+        }
     }
-}
 
-impl CodeGenCtx {
-    fn is_nullable(ty: &TsType) -> bool {
-        match ty {
-            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(_)) => {
-                Self::is_nullable_union(ty)
-            }
-            TsType::TsKeywordType(TsKeywordType { kind, .. }) => matches!(
-                kind,
-                TsKeywordTypeKind::TsNullKeyword | TsKeywordTypeKind::TsUndefinedKeyword
-            ),
-            // For now just assume type references and others are non-null
-            _ => false,
+    /// Like `legal_name`, but also rejects `true`, `false` and `null`, which are valid names
+    /// but not valid enum values. `sanitize_names` prefixes them with `_` instead
+    fn legal_enum_value(&self, enum_name: &str, value: String) -> Result<String> {
+        let value = self.legal_name(enum_name, value)?;
+        match value.as_str() {
+            "true" | "false" | "null" if self.options.sanitize_names => Ok(format!("_{}", value)),
+            "true" | "false" | "null" => Err(anyhow::anyhow!(
+                "`{}` can't be the value of an enum (check: {})",
+                value,
+                enum_name
+            )),
+            _ => Ok(value),
         }
     }
 
-    /// Return true if type is like: `T | null or T | undefined`
-    fn is_nullable_union(ty: &TsType) -> bool {
+    /// Replaces the characters of `name` that aren't allowed in GraphQL names with `_`,
+    /// prefixing it with `_` when it doesn't start with a letter, like `_2fa_enabled` for
+    /// `2fa-enabled`
+    fn sanitize_name(name: &str) -> String {
+        let sanitized = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        match sanitized.chars().next() {
+            Some(c) if c == '_' || c.is_ascii_alphabetic() => sanitized,
+            _ => format!("_{}", sanitized),
+        }
+    }
+
+    /// Parses a method member like `findUser(args: { id: string }): Promise<User>`,
+    /// which is the same as a property with a function type
+    fn parse_method(&mut self, kind: FieldKind, method: &TsMethodSignature) -> Result<ParsedField> {
+        let type_name = self.parent_type_name();
+        let key = Self::member_key(&type_name, &method.key, method.computed)?;
+
+        let ret = match &method.type_ann {
+            Some(ret) => ret,
+            None => return Err(anyhow::anyhow!("Missing return type for method: {}", key)),
+        };
+
+        let (ty, args) = self.with_field_description(method.span, |ctx| {
+            ctx.parse_fn_type(&key, &method.params, &ret.type_ann)
+        })?;
+        let ty = self.apply_id_tag(&key, method.span, ty)?;
+        let ty = self.apply_nullability_tag(&key, method.span, ty)?;
+        let mut directives = self.parse_directives(&key, method.span)?;
+        directives.extend(self.federation_directives(&kind, &key, method.span)?);
+        let name = self.jsdoc_name(&key, method.span)?.unwrap_or(key);
+        let name = self.legal_name(&type_name, name)?;
+
+        let mut field = self.new_parsed_field(kind, name, ty, Some(args), directives)?;
+        field.description(self.jsdoc_description(method.span));
+        Ok(field)
+    }
+
+    /// Runs `parse` with `field_description` set to the description of the property or
+    /// param at `span`
+    fn with_field_description<T>(
+        &mut self,
+        span: Span,
+        parse: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let description = self.jsdoc_description(span);
+        let parent = std::mem::replace(&mut self.field_description, description);
+        let parsed = parse(self);
+        self.field_description = parent;
+        parsed
+    }
+
+    /// Turns the type of a field tagged with `@gqlID` into `ID`, keeping its nullability
+    /// and list wrappers. Only `string` and `number` fields can be tagged
+    fn apply_id_tag(&self, field_name: &str, span: Span, ty: Type_) -> Result<Type_> {
+        if !self.jsdoc_tags(span).iter().any(|(tag, _)| tag == "gqlID") {
+            return Ok(ty);
+        }
+
         match ty {
-            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
-                TsUnionType { types, .. },
-            )) => types.iter().any(|ty| Self::is_nullable(ty)),
-            _ => false,
+            Type_::NonNull { ty } => Ok(Type_::NonNull {
+                ty: Box::new(self.apply_id_tag(field_name, span, *ty)?),
+            }),
+            Type_::List { ty } => Ok(Type_::List {
+                ty: Box::new(self.apply_id_tag(field_name, span, *ty)?),
+            }),
+            Type_::NamedType { name } if name == "String" || name == "Int" || name == "ID" => {
+                Ok(Type_::NamedType { name: "ID".into() })
+            }
+            Type_::NamedType { name } => Err(anyhow::anyhow!(
+                "Only string and number fields can be tagged with @gqlID (check: {}): {}",
+                field_name,
+                name
+            )),
         }
     }
 
-    /// Return the first non-nullable type of a union. This will error if there is no
-    /// nullable type present.
-    ///
-    /// ```
-    /// Ex: "User | null"          -> User
-    ///     "User | string"        -> Error
-    /// ```
-    fn unwrap_union(ty: &TsUnionType) -> Result<&TsType> {
-        match ty.types.iter().find(|t| !Self::is_nullable(t)) {
-            None => Err(anyhow::anyhow!("No non-nullable type found in union")),
-            Some(t) => Ok(t),
+    /// Makes the field at `span` non-null when tagged with `@gqlNonNull` and nullable when
+    /// tagged with `@gqlNullable`, whatever its Typescript type. Only the field itself is
+    /// affected, not the elements of lists, and `force_nullable` still applies after it
+    fn apply_nullability_tag(&self, field_name: &str, span: Span, ty: Type_) -> Result<Type_> {
+        let tags = self.jsdoc_tags(span);
+        let non_null = tags.iter().any(|(tag, _)| tag == "gqlNonNull");
+        let nullable = tags.iter().any(|(tag, _)| tag == "gqlNullable");
+        match (ty, non_null, nullable) {
+            (_, true, true) => Err(anyhow::anyhow!(
+                "Fields can't be tagged with both @gqlNonNull and @gqlNullable (check: {})",
+                field_name
+            )),
+            (Type_::NonNull { ty }, false, true) => Ok(*ty),
+            (ty @ Type_::NonNull { .. }, _, _) | (ty, false, _) => Ok(ty),
+            (ty, true, false) => Ok(Type_::NonNull { ty: Box::new(ty) }),
         }
     }
 
-    /// Computes a name for a new Input type. The resulting name depends on the variant of
-    /// `ComputeNameKind`. If it is `ComputeNameKind::Input` we try to generate a unique
-    /// input name based on `field_name` and `param_name`
-    ///
-    /// Otherwise, we also concatenate the name of the param
-    fn compute_new_name(kind: ComputeNameKind, field_name: &str) -> String {
-        match kind {
-            ComputeNameKind::Output => {
-                format!("{}{}", upper_camel_case(field_name), "Output")
+    /// Returns the `@gqlDirective` tags in the JSDoc comment of the member at `span`,
+    /// which are attached verbatim to the field
+    fn parse_directives(&self, field_name: &str, span: Span) -> Result<Vec<String>> {
+        self.jsdoc_tags(span)
+            .into_iter()
+            .filter(|(tag, _)| tag == "gqlDirective")
+            .map(|(_, directive)| {
+                if Self::is_valid_directive(&directive) {
+                    Ok(directive)
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Invalid directive, expected `name` or `name(args)` (check: {}): {}",
+                        field_name,
+                        directive
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns a federation `@key` directive for each `@gqlKey` tag on the type at `span`,
+    /// whose value is the key's field set like `id` or `sku variation { id }`
+    fn key_directives(&self, type_name: &str, span: Span) -> Result<Vec<String>> {
+        self.jsdoc_tags(span)
+            .into_iter()
+            .filter(|(tag, _)| tag == "gqlKey")
+            .map(|(_, fields)| {
+                if fields.is_empty() || fields.contains('"') {
+                    Err(anyhow::anyhow!(
+                        "Invalid @gqlKey, expected the fields of the key (check: {})",
+                        type_name
+                    ))
+                } else {
+                    Ok(format!("key(fields: \"{}\")", fields))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the federation `@external`, `@requires` and `@provides` directives of the
+    /// `@gqlExternal`, `@gqlRequires` and `@gqlProvides` tags on the field at `span`, whose
+    /// values are field sets like `@gqlKey`. Only object fields can be tagged
+    fn federation_directives(
+        &self,
+        kind: &FieldKind,
+        field_name: &str,
+        span: Span,
+    ) -> Result<Vec<String>> {
+        let mut directives = Vec::new();
+        for (tag, fields) in self.jsdoc_tags(span) {
+            let directive = match tag.as_str() {
+                "gqlExternal" => "external",
+                "gqlRequires" => "requires",
+                "gqlProvides" => "provides",
+                _ => continue,
+            };
+            if let FieldKind::Input = kind {
+                return Err(anyhow::anyhow!(
+                    "@{} only applies to object fields (check: {})",
+                    tag,
+                    field_name
+                ));
             }
-            ComputeNameKind::Input(_param_name, member_count) if member_count == 1 => {
-                format!("{}{}", upper_camel_case(field_name), "Input",)
+            match directive {
+                "external" => directives.push(directive.to_string()),
+                _ if fields.is_empty() || fields.contains('"') => {
+                    return Err(anyhow::anyhow!(
+                        "Invalid @{}, expected a field set (check: {})",
+                        tag,
+                        field_name
+                    ))
+                }
+                _ => directives.push(format!("{}(fields: \"{}\")", directive, fields)),
             }
-            ComputeNameKind::Input(param_name, _) => {
-                format!(
-                    "{}{}{}",
-                    upper_camel_case(field_name),
-                    "Input",
-                    upper_camel_case(param_name)
-                )
+        }
+        Ok(directives)
+    }
+
+    /// Returns the name given by a `@gqlName` tag on the node at `span`, which replaces the
+    /// Typescript name `ts_name` in the schema
+    fn jsdoc_name(&self, ts_name: &str, span: Span) -> Result<Option<String>> {
+        match self
+            .jsdoc_tags(span)
+            .into_iter()
+            .find(|(tag, _)| tag == "gqlName")
+        {
+            Some((_, name)) if Self::is_valid_name(&name) => Ok(Some(name)),
+            Some((_, name)) => Err(anyhow::anyhow!(
+                "Invalid @gqlName, expected a GraphQL name (check: {}): {}",
+                ts_name,
+                name
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the node at `span` is tagged with `@gqlIgnore`, which leaves it out of the
+    /// schema
+    fn is_ignored(&self, span: Span) -> bool {
+        self.jsdoc_tags(span)
+            .iter()
+            .any(|(tag, _)| tag == "gqlIgnore")
+    }
+
+    fn is_ignored_member(&self, member: &TsTypeElement) -> bool {
+        match member {
+            TsTypeElement::TsPropertySignature(TsPropertySignature { span, .. })
+            | TsTypeElement::TsMethodSignature(TsMethodSignature { span, .. }) => {
+                self.is_ignored(*span)
             }
+            _ => false,
         }
     }
 
-    fn parse_keyword_type(kind: &TsKeywordTypeKind) -> Result<Type_> {
-        match kind {
-            TsKeywordTypeKind::TsNumberKeyword => Ok(Type_::NamedType { name: "Int".into() }),
-            TsKeywordTypeKind::TsStringKeyword => Ok(Type_::NamedType {
-                name: "String".into(),
-            }),
-            TsKeywordTypeKind::TsBooleanKeyword => Ok(Type_::NamedType {
-                name: "Boolean".into(),
-            }),
-            // TODO: Scalar types like BigInt
-            r => todo!("Unsupported keyword type: {:?}", r),
+    /// Returns the `@tag value` pairs of the JSDoc comments leading the node at `span`
+    fn jsdoc_tags(&self, span: Span) -> Vec<(String, String)> {
+        self.jsdoc_lines(span)
+            .iter()
+            .filter_map(|line| line.strip_prefix('@'))
+            .map(|line| match line.split_once(char::is_whitespace) {
+                Some((tag, value)) => (tag.to_string(), value.trim().to_string()),
+                None => (line.to_string(), String::new()),
+            })
+            .collect()
+    }
+
+    /// Returns the text of the JSDoc comments leading the node at `span` up to their first
+    /// tag, or `None` if there is no such text
+    fn jsdoc_description(&self, span: Span) -> Option<String> {
+        let description = self
+            .jsdoc_lines(span)
+            .iter()
+            .take_while(|line| !line.starts_with('@'))
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let description = description.trim();
+
+        if description.is_empty() {
+            None
+        } else {
+            Some(description.to_string())
         }
     }
-}
 
-fn upper_camel_case(s: &str) -> String {
-    s.chars()
-        .next()
-        .iter()
-        .map(|c| c.to_ascii_uppercase())
-        .chain(s.chars().skip(1))
-        .collect::<String>()
-}
+    /// Returns the reason of a `@deprecated` JSDoc tag on the node at `span`
+    fn jsdoc_deprecation(&self, span: Span) -> Option<String> {
+        self.jsdoc_tags(span)
+            .into_iter()
+            .find(|(tag, _)| tag == "deprecated")
+            .map(|(_, reason)| match reason.as_str() {
+                // The encoder always writes a reason, so use the GraphQL default
+                "" => "No longer supported".to_string(),
+                _ => reason,
+            })
+    }
 
-pub fn parse_ts(s: &str, opts: &str) -> Result<Program> {
-    let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
-    let c = Arc::new(Compiler::new(cm));
+    /// Returns the value of a `@default` JSDoc tag on the arg or input field at `span`,
+    /// written verbatim as the GraphQL default (e.g. `10`, `"name"` or `ACTIVE`).
+    ///
+    /// Parameters in type positions can't have initializers (`limit = 10` is only valid in
+    /// implementations) and swc doesn't keep them in `TsFnParam`, so the tag is the only
+    /// source of defaults
+    fn jsdoc_default(&self, span: Span) -> Option<String> {
+        self.jsdoc_tags(span)
+            .into_iter()
+            .find(|(tag, value)| tag == "default" && !value.is_empty())
+            .map(|(_, value)| value)
+    }
 
-    try_with_handler(c.cm.clone(), |handler| {
-        let opts: ParseOptions = serde_json::from_str(opts).unwrap();
+    /// Returns the lines of the JSDoc (`/** */`) comments leading the node at `span`,
+    /// without the comment markers
+    fn jsdoc_lines(&self, span: Span) -> Rc<Vec<String>> {
+        // Synthesized nodes have no comments
+        if span.is_dummy() {
+            return Rc::default();
+        }
+        if let Some(lines) = self.jsdoc.borrow().get(&span.lo) {
+            return lines.clone();
+        }
 
-        let fm = c.cm.new_source_file(FileName::Anon, s.into());
-        let program = c
-            .parse_js(
-                fm,
-                handler,
-                opts.target,
-                opts.syntax,
-                opts.is_module,
-                opts.comments,
-            )
-            .context("failed to parse code")?;
+        let lines = match self.comments.get_leading(span.lo) {
+            Some(comments) => comments
+                .iter()
+                .filter(|c| c.kind == CommentKind::Block && c.text.starts_with('*'))
+                .flat_map(|c| {
+                    c.text
+                        .lines()
+                        .map(|line| line.trim().trim_start_matches('*').trim().to_string())
+                        .collect::<Vec<String>>()
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let lines = Rc::new(lines);
+        self.jsdoc.borrow_mut().insert(span.lo, lines.clone());
+        lines
+    }
 
-        Ok(program)
-    })
-}
+    /// Checks that a directive looks like `name` or `name(args)`, the args are not
+    /// validated beyond being wrapped in parentheses
+    fn is_valid_directive(directive: &str) -> bool {
+        let (name, args) = match directive.find('(') {
+            Some(idx) => directive.split_at(idx),
+            None => (directive, ""),
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use indoc::indoc;
+        Self::is_valid_name(name) && (args.is_empty() || (args.len() > 2 && args.ends_with(')')))
+    }
 
-    fn get_prog(src: &str) -> Program {
-        parse_ts(
-            src,
-            "{
-                \"syntax\": \"typescript\",
-                \"tsx\": true,
-                \"decorators\": false,
-                \"dynamicImport\": false
-          }",
-        )
-        .unwrap()
+    fn is_valid_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        matches!(chars.next(), Some(c) if c == '_' || c.is_ascii_alphabetic())
+            && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
     }
 
-    fn test(src: &str, expected: &str, mani: Vec<(&str, GraphQLKind)>) {
-        let prog = get_prog(src);
+    fn new_parsed_field(
+        &self,
+        kind: FieldKind,
+        key: String,
+        ty: Type_,
+        args: Option<Vec<InputValue>>,
+        directives: Vec<String>,
+    ) -> Result<ParsedField> {
+        let ty = match kind {
+            FieldKind::Object if self.options.force_nullable => Self::into_nullable(ty),
+            _ => ty,
+        };
 
-        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
-        mani.into_iter().for_each(|(k, v)| {
-            map.insert(k.into(), v);
-        });
+        let mut field = match args {
+            None => ParsedField::new(kind, key, ty),
+            Some(args) => match ParsedField::with_args(kind, key, ty, args) {
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "Only ObjectDefs can contain input fields with args"
+                    ))
+                }
+                Some(field) => field,
+            },
+        };
+        field.directives = directives;
+
+        Ok(field)
+    }
 
-        let mut gen = CodeGenCtx::new(map);
+    /// Returns the type of a GraphQL field, returning arguments if it has any.
+    /// `field_name` is used to generate names for Inputs, and can be an empty string
+    /// if you don't expect the Typescript type to be a function
+    ///
+    /// Important thing to note is that we only consider a sub-set of types, because
+    /// the Typescript code is widened by the Typescript Compiler API before we receive it.
+    fn parse_type(
+        &mut self,
+        field_name: &str,
+        type_ann: &TsType,
+        optional: bool,
+    ) -> Result<(Type_, Option<Vec<InputValue>>)> {
+        let (ty, args) = match type_ann {
+            // `Pick` and `Omit` are synthesized like inline objects, so they are an
+            // input or an object depending on where they are used
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(ident),
+                ..
+            }) if PICK_TYPES.contains(&ident.sym.as_ref()) => {
+                let lit = self.pick_type(field_name, type_ann)?.unwrap();
+                return self.parse_type(field_name, &lit, optional);
+            }
+            TsType::TsKeywordType(TsKeywordType { kind, .. }) => {
+                (self.parse_keyword_type(field_name, kind)?, None)
+            }
+            TsType::TsArrayType(TsArrayType { elem_type, .. }) => {
+                (
+                    Type_::List {
+                        // Elements are only nullable when they are declared as a
+                        // nullable union, like `Array<User | null>`
+                        ty: Box::new(
+                            self.parse_type(field_name, elem_type, self.is_nullable(elem_type)?)?
+                                .0,
+                        ),
+                    },
+                    None,
+                )
+            }
+            // Inline objects in the return type of a field with args, possibly nested in
+            // arrays and nullable unions
+            TsType::TsTypeLit(_) if self.parsing_output => {
+                let name = self.compute_new_name(ComputeNameKind::Output, field_name);
+                let name = self.parse_type_literal(
+                    FieldKind::Object,
+                    &name,
+                    type_ann,
+                    self.field_description.clone(),
+                )?;
+                (Type_::NamedType { name }, None)
+            }
+            // Inline objects on fields of other types are named after the type and field,
+            // like `UserAddress` for `User.address`
+            TsType::TsTypeLit(_) => {
+                let (parent, kind) = match self.parent_types.last() {
+                    Some(parent) => parent.clone(),
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Inline object types are only supported on fields (check: {})",
+                            field_name
+                        ))
+                    }
+                };
+                let name = format!("{}{}", parent, upper_camel_case(field_name));
+                let name =
+                    self.parse_type_literal(kind, &name, type_ann, self.field_description.clone())?;
+                (Type_::NamedType { name }, None)
+            }
+            TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. }) => {
+                return self.parse_type(field_name, type_ann, optional)
+            }
+            // Conditional types aren't evaluated, they are only supported when both
+            // branches map to the same GraphQL type
+            TsType::TsConditionalType(TsConditionalType {
+                true_type,
+                false_type,
+                ..
+            }) => {
+                // Nullability is applied below, based on the optionality of the field
+                let (true_ty, true_args) = self.parse_type(field_name, true_type, true)?;
+                let (false_ty, false_args) = self.parse_type(field_name, false_type, true)?;
+                if true_args.is_some()
+                    || false_args.is_some()
+                    || true_ty.to_string() != false_ty.to_string()
+                {
+                    return Err(anyhow::anyhow!(
+                        "Field `{}` has a conditional type whose branches resolve to different types ({} and {}), which can't be evaluated",
+                        field_name,
+                        true_ty,
+                        false_ty
+                    ));
+                }
+                (true_ty, None)
+            }
+            // A union that includes `null` stays nullable wherever it is referenced
+            TsType::TsTypeRef(TsTypeRef {
+                type_name,
+                type_params: None,
+                ..
+            }) if self.is_nullable_union_ref(type_name) => {
+                return self.parse_type_ref(field_name, type_name, &None)
+            }
+            TsType::TsTypeRef(TsTypeRef {
+                type_name,
+                type_params,
+                ..
+            }) => self.parse_type_ref(field_name, type_name, type_params)?,
+            // The type of a value isn't known, so `typeof x` only resolves when `x` is
+            // also the name of a type in the manifest
+            TsType::TsTypeQuery(TsTypeQuery { expr_name, .. }) => {
+                let type_name = match expr_name {
+                    TsTypeQueryExpr::TsEntityName(type_name) => type_name,
+                    TsTypeQueryExpr::Import(_) => {
+                        return Err(anyhow::anyhow!(
+                            "Field `{}` uses `typeof import(...)`, which can't be resolved",
+                            field_name
+                        ))
+                    }
+                };
+                let name = match type_name {
+                    TsEntityName::Ident(ident) => ident.sym.to_string(),
+                    TsEntityName::TsQualifiedName(name) => self.qualified_type_name(name),
+                };
+                let name = self.resolve_import(&name).to_string();
+                if !self.manifest.contains_key(&name) {
+                    return Err(anyhow::anyhow!(
+                        "Field `{}` uses `typeof {}`, which can only be resolved when {} is a type in the manifest",
+                        field_name,
+                        name,
+                        name
+                    ));
+                }
+                self.parse_type_ref(field_name, type_name, &None)?
+            }
+            TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(TsFnType {
+                params,
+                // `type_ann` here is return type
+                type_ann,
+                ..
+            })) => {
+                let (ret_ty, args) = self.parse_fn_type(field_name, params, &type_ann.type_ann)?;
+                return Ok((ret_ty, Some(args)));
+            }
+            // `readonly T[]` is the same as `T[]` in GraphQL
+            TsType::TsTypeOperator(TsTypeOperator {
+                op: TsTypeOperatorOp::ReadOnly,
+                type_ann,
+                ..
+            }) => return self.parse_type(field_name, type_ann, optional),
+            TsType::TsTypeOperator(TsTypeOperator {
+                op: TsTypeOperatorOp::KeyOf,
+                type_ann,
+                ..
+            }) => (self.parse_keyof(field_name, type_ann)?, None),
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(uni)) => {
+                let literal_union = match Self::string_literal_union(uni) {
+                    Some(values) => Some(self.parse_literal_union(field_name, values)?),
+                    // Numbers can't be enum values, so number literals keep their scalar
+                    None => match Self::number_literal_union(uni) {
+                        Some(scalar) => {
+                            self.check_coercion(
+                                &format!("Field `{}`", field_name),
+                                "a union of number literals",
+                                scalar,
+                            )?;
+                            Some(Type_::NamedType {
+                                name: scalar.into(),
+                            })
+                        }
+                        None => None,
+                    },
+                };
+                if let Some(ty) = literal_union {
+                    if optional || self.is_nullable_union(type_ann)? {
+                        return Ok((ty, None));
+                    }
+                    return Ok((Type_::NonNull { ty: Box::new(ty) }, None));
+                }
+
+                // Unions without `null` or `undefined` members keep being nullable
+                let nullable = optional
+                    || !Self::is_nullish_union(type_ann)
+                    || self.is_nullable_union(type_ann)?;
+                let typ = Self::unwrap_union(uni)?;
+                return self.parse_type(field_name, typ, nullable);
+            }
+            // GraphQL can't express patterns like `user_${string}`
+            TsType::TsLitType(TsLitType {
+                lit: TsLit::Tpl(_), ..
+            }) => {
+                self.check_coercion(
+                    &format!("Field `{}`", field_name),
+                    "a template literal",
+                    "String",
+                )?;
+                self.diagnostics
+                    .push(Diagnostic::TemplateLiteral(field_name.to_string()));
+                (
+                    Type_::NamedType {
+                        name: "String".into(),
+                    },
+                    None,
+                )
+            }
+            ty => {
+                return Err(anyhow::anyhow!(
+                    "Field `{}` has {}, which has no GraphQL representation",
+                    field_name,
+                    Self::describe_type(ty)
+                ))
+            }
+        };
+
+        if !optional {
+            return Ok((Type_::NonNull { ty: Box::new(ty) }, args));
+        }
+
+        Ok((ty, args))
+    }
+
+    /// Returns the return type and args of a field declared as a function
+    fn parse_fn_type(
+        &mut self,
+        field_name: &str,
+        params: &[TsFnParam],
+        ret: &TsType,
+    ) -> Result<(Type_, Vec<InputValue>)> {
+        self.parsing_inputs = true;
+        // Types synthesized for args are named after the field's inputs, like
+        // `UsersInputTagsKeyValue` for the `tags` arg of `users`
+        let mut input_name = upper_camel_case(field_name);
+        input_name.push_str(&self.options.input_suffix);
+        self.parent_types.push((input_name, FieldKind::Input));
+        let args = self.parse_fn_args(field_name, params);
+        self.parent_types.pop();
+        let args = args?;
+        self.parsing_inputs = false;
+
+        self.parsing_output = true;
+        // Last param can be anything here, since we don't know if the return type is
+        // optional until we parse it. `self.parse_type()` will make sure to return
+        // the correct type if we are parsing return type
+        let (ret_ty, _) = self.parse_type(field_name, ret, true)?;
+        self.parsing_output = false;
+
+        Ok((ret_ty, args))
+    }
+
+    fn parse_fn_args(&mut self, field_name: &str, params: &[TsFnParam]) -> Result<Vec<InputValue>> {
+        let args = match Self::object_literal_param(params) {
+            // A single object literal param, its members become the field's args. When
+            // the param itself is optional every arg may be missing
+            Some((lit, optional)) => {
+                let members = lit
+                    .members
+                    .iter()
+                    .filter(|m| !self.is_ignored_member(m))
+                    .collect::<Vec<&TsTypeElement>>();
+                members
+                    .iter()
+                    .map(|f| self.parse_arg_member(field_name, f, members.len(), optional))
+                    .collect::<Result<Vec<ParsedArg>>>()?
+            }
+            // Otherwise each param is an arg of its own
+            None => {
+                let params = params
+                    .iter()
+                    .filter(|p| match p {
+                        TsFnParam::Ident(BindingIdent { id, .. }) => !self.is_ignored(id.span),
+                        _ => true,
+                    })
+                    .collect::<Vec<&TsFnParam>>();
+                params
+                    .iter()
+                    .map(|p| self.parse_fn_param(field_name, p, params.len()))
+                    .collect::<Result<Vec<ParsedArg>>>()?
+            }
+        };
+        let mut names = HashSet::new();
+        if let Some(arg) = args.iter().find(|arg| !names.insert(arg.name.as_str())) {
+            return Err(anyhow::anyhow!(
+                "Duplicate arg of field {}: {}",
+                field_name,
+                arg.name
+            ));
+        }
+        Ok(self.order_args(args))
+    }
+
+    /// Sorts the args of a field according to `arg_order`
+    fn order_args(&self, mut args: Vec<ParsedArg>) -> Vec<InputValue> {
+        match self.options.arg_order {
+            ArgOrder::Source => {}
+            ArgOrder::Alphabetical => args.sort_by(|a, b| a.name.cmp(&b.name)),
+            ArgOrder::RequiredFirst => args.sort_by_key(|arg| !arg.required),
+        }
+        args.into_iter().map(|arg| arg.value).collect()
+    }
+
+    fn parse_type_ref(
+        &mut self,
+        field_name: &str,
+        type_name: &TsEntityName,
+        type_params: &Option<TsTypeParamInstantiation>,
+    ) -> Result<(Type_, Option<Vec<InputValue>>)> {
+        let qualified;
+        let wrapper = match type_name {
+            TsEntityName::Ident(ident) => ident.sym.as_ref(),
+            TsEntityName::TsQualifiedName(name) => {
+                qualified = self.qualified_type_name(name);
+                qualified.as_str()
+            }
+        };
+        if LIST_WRAPPERS.contains(&wrapper) {
+            let elem_type = match type_params {
+                Some(TsTypeParamInstantiation { params, .. }) if params.len() == 1 => &params[0],
+                Some(TsTypeParamInstantiation { params, .. }) => {
+                    return Err(anyhow::anyhow!(
+                        "Invalid amount of type parameters for {}: {}",
+                        wrapper,
+                        params.len()
+                    ))
+                }
+                None => return Err(anyhow::anyhow!("Missing type parameter for {}", wrapper)),
+            };
+            // Same as arrays, elements are only nullable when declared as a nullable union
+            let (ty, _) = self.parse_type(field_name, elem_type, self.is_nullable(elem_type)?)?;
+            return Ok((Type_::List { ty: Box::new(ty) }, None));
+        }
+        if MAP_WRAPPERS.contains(&wrapper) {
+            return match type_params {
+                Some(TsTypeParamInstantiation { params, .. }) if params.len() == 2 => {
+                    Ok((self.parse_map(field_name, &params[0], &params[1])?, None))
+                }
+                Some(TsTypeParamInstantiation { params, .. }) => Err(anyhow::anyhow!(
+                    "Invalid amount of type parameters for {}: {}",
+                    wrapper,
+                    params.len()
+                )),
+                None => Err(anyhow::anyhow!("Missing type parameters for {}", wrapper)),
+            };
+        }
+        if wrapper == "Exclude" || wrapper == "Extract" {
+            return Ok((
+                self.parse_literal_filter(field_name, wrapper, type_params)?,
+                None,
+            ));
+        }
+        if !ASYNC_WRAPPERS.contains(&wrapper) {
+            // Imported types are referred to by the name they were exported under
+            let name = self.resolve_import(wrapper).to_string();
+            if let Some(TsTypeParamInstantiation { params, .. }) = type_params {
+                if self.generic_aliases.contains_key(&name) {
+                    let name = self.instantiate_generic(field_name, &name, params)?;
+                    return Ok((Type_::NamedType { name }, None));
+                }
+            }
+            if let Some(scalar) = self.scalar_alias(&name) {
+                return Ok((
+                    Type_::NamedType {
+                        name: scalar.to_string(),
+                    },
+                    None,
+                ));
+            }
+            // Fields of input types can reference other inputs, just like args
+            let parsing_inputs = self.parsing_inputs
+                || matches!(self.parent_types.last(), Some((_, FieldKind::Input)));
+            match self.manifest.get(&name) {
+                Some(&GraphQLKind::Object) if parsing_inputs && self.options.mirror_inputs => {
+                    let properties = match self.object_properties.get(&name) {
+                        Some(properties) => properties.clone(),
+                        None => {
+                            return Err(anyhow::anyhow!(
+                                "Only object types declared with a type literal or interface can be mirrored as inputs (check: {})",
+                                name
+                            ))
+                        }
+                    };
+                    let mut input_name = self.graphql_type_name(&name);
+                    input_name.push_str(&self.options.input_suffix);
+                    let name =
+                        self.parse_type_literal(FieldKind::Input, &input_name, &properties, None)?;
+                    Ok((Type_::NamedType { name }, None))
+                }
+                Some(&GraphQLKind::Object | &GraphQLKind::Interface | &GraphQLKind::Union)
+                    if parsing_inputs =>
+                {
+                    Err(anyhow::anyhow!(
+                        "Field args can only be Inputs (check: {})",
+                        name
+                    ))
+                }
+                Some(&GraphQLKind::Input) if !parsing_inputs => Err(anyhow::anyhow!(
+                    "Field type can't be an Input (check: {})",
+                    name
+                )),
+                Some(_) => {
+                    let name = self.graphql_type_name(&name);
+                    self.reference(&name);
+                    Ok((Type_::NamedType { name }, None))
+                }
+                None => match self.imports.get(wrapper) {
+                    Some((_, src)) => Err(anyhow::anyhow!(
+                        "Undefined type: {} (imported from {})",
+                        name,
+                        src
+                    )),
+                    None => Err(anyhow::anyhow!("Undefined type: {}", name)),
+                },
+            }
+        } else {
+            match type_params {
+                None => Err(anyhow::anyhow!("Missing type parameter for {}", wrapper)),
+                Some(TsTypeParamInstantiation { params, .. }) => {
+                    match (wrapper, params.len()) {
+                        (_, 1) => {}
+                        // `AsyncGenerator<T, TReturn, TNext>`, only the yielded type is used
+                        ("AsyncGenerator" | "AsyncIterator" | "AsyncIterableIterator", 2..=3) => {}
+                        (_, other) => {
+                            return Err(anyhow::anyhow!(
+                                "Invalid amount of type parameters for {}: {}",
+                                wrapper,
+                                other
+                            ))
+                        }
+                    }
+                    let mut typ = &params[0];
+                    // `Promise<Promise<T>>` resolves to `T` just like `Promise<T>`
+                    while let TsType::TsTypeRef(TsTypeRef {
+                        type_name: TsEntityName::Ident(ident),
+                        type_params: Some(TsTypeParamInstantiation { params, .. }),
+                        ..
+                    }) = &**typ
+                    {
+                        match (ident.sym.as_ref(), params.len()) {
+                            ("Promise", 1) => typ = &params[0],
+                            _ => break,
+                        }
+                    }
+
+                    // Somewhat confusing, but if we are here then we are parsing return of
+                    // a field with arguments, meaning we don't know the optionality of the
+                    // return type until we unwrap it from the Promise, meaning we should
+                    // discard the `optional` param and return here
+                    //
+                    // Maybe we should move this match branch into its own dedicated function,
+                    // and when we parse the return we call that instead of this function.
+                    let nullable = self.is_nullable_union(typ)?;
+                    match &**typ {
+                        TsType::TsUnionOrIntersectionType(
+                            TsUnionOrIntersectionType::TsUnionType(u),
+                        ) if nullable => {
+                            let non_null = Self::unwrap_union(u)?;
+                            match non_null {
+                                TsType::TsTypeLit(_) => {
+                                    let name =
+                                        self.compute_new_name(ComputeNameKind::Output, field_name);
+                                    let name = self.parse_type_literal(
+                                        FieldKind::Object,
+                                        &name,
+                                        non_null,
+                                        self.field_description.clone(),
+                                    )?;
+
+                                    Ok((Type_::NamedType { name }, None))
+                                }
+                                _ => self.parse_type(field_name, non_null, true),
+                            }
+                        }
+                        TsType::TsTypeLit(_) => {
+                            let name = self.compute_new_name(ComputeNameKind::Output, field_name);
+                            let name = self.parse_type_literal(
+                                FieldKind::Object,
+                                &name,
+                                typ,
+                                self.field_description.clone(),
+                            )?;
+                            Ok((
+                                Type_::NonNull {
+                                    ty: Box::new(Type_::NamedType { name }),
+                                },
+                                None,
+                            ))
+                        }
+                        _ => self.parse_type(field_name, typ, false),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the name a qualified reference like `Schema.User` is looked up by, the
+    /// last segment unless `qualified_name_separator` is set
+    fn qualified_type_name(&self, name: &TsQualifiedName) -> String {
+        let separator = match &self.options.qualified_name_separator {
+            Some(separator) => separator,
+            None => return name.right.sym.to_string(),
+        };
+
+        let left = match &name.left {
+            TsEntityName::Ident(ident) => ident.sym.to_string(),
+            TsEntityName::TsQualifiedName(left) => self.qualified_type_name(left),
+        };
+        format!("{}{}{}", left, separator, name.right.sym)
+    }
+
+    fn parse_arg_member(
+        &mut self,
+        field_name: &str,
+        member: &TsTypeElement,
+        member_count: usize,
+        optional_param: bool,
+    ) -> Result<ParsedArg> {
+        match member {
+            TsTypeElement::TsPropertySignature(prop_sig) => {
+                let key = Self::member_key(field_name, &prop_sig.key, prop_sig.computed)?;
+                let key = self.legal_name(field_name, key)?;
+
+                let type_ann = match &prop_sig.type_ann {
+                    Some(t) => t,
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Arg `{}` of field `{}` has no type annotation",
+                            key,
+                            field_name
+                        ))
+                    }
+                };
+
+                // An optional arg with a default is never null, so it stays non-null
+                let default = self.jsdoc_default(prop_sig.span);
+                let ty = self.with_field_description(prop_sig.span, |ctx| {
+                    ctx.parse_arg(
+                        field_name,
+                        &key,
+                        &type_ann.type_ann,
+                        (prop_sig.optional || optional_param) && default.is_none(),
+                        member_count,
+                    )
+                })?;
+                self.new_parsed_arg(field_name, &key, ty, default, prop_sig.span)
+            }
+            _ => Err(anyhow::anyhow!(
+                "Field args input can only contain properties"
+            )),
+        }
+    }
+
+    /// Creates the arg `name` of `field_name`, with the description and deprecation of
+    /// the JSDoc comment at `span`. Only optional args can be deprecated
+    fn new_parsed_arg(
+        &self,
+        field_name: &str,
+        name: &str,
+        ty: Type_,
+        default: Option<String>,
+        span: Span,
+    ) -> Result<ParsedArg> {
+        let required = matches!(ty, Type_::NonNull { .. }) && default.is_none();
+        let mut value = InputValue::new(name.to_string(), ty);
+        value.description(self.jsdoc_description(span));
+        value.default(default);
+        if let Some(reason) = self.jsdoc_deprecation(span) {
+            if required {
+                return Err(anyhow::anyhow!(
+                    "Arg `{}` of field `{}` is required and can't be deprecated, make it optional or give it a @default",
+                    name,
+                    field_name
+                ));
+            }
+            value.deprecated(Some(reason));
+        }
+        Ok(ParsedArg {
+            name: name.to_string(),
+            required,
+            value,
+        })
+    }
+
+    /// Parses a parameter of a field with multiple params, or a single param that
+    /// isn't an object literal, into an arg named after the parameter
+    fn parse_fn_param(
+        &mut self,
+        field_name: &str,
+        param: &TsFnParam,
+        param_count: usize,
+    ) -> Result<ParsedArg> {
+        match param {
+            TsFnParam::Ident(BindingIdent { id, type_ann }) => {
+                let type_ann = match type_ann {
+                    Some(t) => t,
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Missing type annotation for parameter: {}",
+                            id.sym.as_ref()
+                        ))
+                    }
+                };
+
+                // `id.optional` is set for params declared like `limit?: number`, which
+                // stay non-null when they have a default
+                let default = self.jsdoc_default(id.span);
+                let ty = self.with_field_description(id.span, |ctx| {
+                    ctx.parse_arg(
+                        field_name,
+                        id.sym.as_ref(),
+                        &type_ann.type_ann,
+                        id.optional && default.is_none(),
+                        param_count,
+                    )
+                })?;
+                let name = self.legal_name(field_name, id.sym.to_string())?;
+                self.new_parsed_arg(field_name, &name, ty, default, id.span)
+            }
+            _ => Err(anyhow::anyhow!(
+                "Field args can only be declared with named parameters"
+            )),
+        }
+    }
+
+    fn parse_arg(
+        &mut self,
+        field_name: &str,
+        name: &str,
+        ty: &TsType,
+        optional: bool,
+        member_count: usize,
+    ) -> Result<Type_> {
+        if let Some(lit) = self.pick_type(field_name, ty)? {
+            return self.parse_arg(field_name, name, &lit, optional, member_count);
+        }
+        let type_ = match ty {
+            TsType::TsTypeLit(_) => {
+                let input_name =
+                    self.compute_new_name(ComputeNameKind::Input(name, member_count), field_name);
+                self.parse_arg_type_literal(&input_name, ty, optional)?
+            }
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(uni)) => {
+                if !Self::is_nullish_union(ty) {
+                    return Err(anyhow::anyhow!("Unions as field args must be nullable"));
+                }
+                let nullable = optional || self.is_nullable_union(ty)?;
+                let unwrapped = Self::unwrap_union(uni)?;
+                match unwrapped {
+                    TsType::TsTypeLit(_) => {
+                        let input_name = self.compute_new_name(
+                            ComputeNameKind::Input(name, member_count),
+                            field_name,
+                        );
+                        self.parse_arg_type_literal(&input_name, unwrapped, nullable)?
+                    }
+                    _ => {
+                        let (ty, _) = self.parse_type(name, unwrapped, nullable)?;
+                        ty
+                    }
+                }
+            }
+            ty => {
+                let (ty, _) = self.parse_type(name, ty, optional)?;
+                ty
+            }
+        };
+
+        Ok(type_)
+    }
+
+    fn parse_arg_type_literal(&mut self, name: &str, ty: &TsType, optional: bool) -> Result<Type_> {
+        let name =
+            self.parse_type_literal(FieldKind::Input, name, ty, self.field_description.clone())?;
+
+        if !optional {
+            Ok(Type_::NonNull {
+                ty: Box::new(Type_::NamedType { name }),
+            })
+        } else {
+            Ok(Type_::NamedType { name })
+        }
+    }
+
+    /// Emits a new type named `new_name` from a type literal, returning the name the
+    /// type should be referenced by
+    fn parse_type_literal(
+        &mut self,
+        kind: FieldKind,
+        new_name: &str,
+        ty: &TsType,
+        description: Option<String>,
+    ) -> Result<String> {
+        if let Some(scalar) = self.index_signature_scalar(ty) {
+            self.check_coercion(
+                &format!("Type `{}`", new_name),
+                "an index signature type",
+                &scalar,
+            )?;
+            self.declare_scalar(&scalar);
+            return Ok(scalar);
+        }
+
+        // Literals can't contain themselves, cycles only go through references to named
+        // types, and nested literals are named after their path, so this never recurses
+        let fields = self.parse_typed_fields(kind.clone(), new_name, ty)?;
+        self.define_synthesized(kind, new_name, fields, description)
+    }
+
+    /// Emits the input or object `new_name` synthesized with `fields`, unless an
+    /// identical one was already emitted. Descriptions aren't compared, the first
+    /// one is kept
+    fn define_synthesized(
+        &mut self,
+        kind: FieldKind,
+        new_name: &str,
+        fields: Vec<ParsedField>,
+        description: Option<String>,
+    ) -> Result<String> {
+        match kind {
+            FieldKind::Input => {
+                let mut input_def = InputObjectDef::new(new_name.into());
+                let directives = Directives::of_fields(&fields);
+                fields
+                    .into_iter()
+                    .for_each(|f| input_def.field(f.input().unwrap()));
+
+                let sdl = directives.attach(new_name, input_def.to_string());
+                input_def.description(description);
+                if self.register_synthesized(new_name, sdl)? {
+                    self.definitions.push(Definition::Input {
+                        name: new_name.to_string(),
+                        def: input_def,
+                        directives,
+                    });
+                }
+
+                Ok(new_name.to_string())
+            }
+            FieldKind::Object => {
+                let sdl = Self::object_def(new_name.to_string(), fields.clone(), &[]).to_string();
+                let sdl = Directives::of_fields(&fields).attach(new_name, sdl);
+
+                if self.register_synthesized(new_name, sdl)? {
+                    self.definitions.push(Definition::Object {
+                        name: new_name.to_string(),
+                        description,
+                        fields,
+                    });
+                }
+
+                Ok(new_name.to_string())
+            }
+        }
+    }
+
+    /// Returns the type of a field declared as `Map<K, V>`, according to
+    /// `map_representation`
+    fn parse_map(&mut self, field_name: &str, key: &TsType, value: &TsType) -> Result<Type_> {
+        match self.options.map_representation.clone() {
+            Some(MapRepresentation::Scalar(name)) => {
+                self.check_coercion(&format!("Field `{}`", field_name), "a `Map`", &name)?;
+                self.declare_scalar(&name);
+                Ok(Type_::NamedType { name })
+            }
+            Some(MapRepresentation::KeyValueList) => {
+                let (parent, kind) = match self.parent_types.last() {
+                    Some(parent) => parent.clone(),
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Maps are only supported on fields (check: {})",
+                            field_name
+                        ))
+                    }
+                };
+                let kind = if self.parsing_inputs {
+                    FieldKind::Input
+                } else {
+                    kind
+                };
+                let name = format!("{}{}KeyValue", parent, upper_camel_case(field_name));
+
+                let (key_ty, _) = self.parse_type(field_name, key, false)?;
+                let (value_ty, _) = self.parse_type(field_name, value, self.is_nullable(value)?)?;
+                let fields = vec![
+                    self.new_parsed_field(kind.clone(), "key".into(), key_ty, None, Vec::new())?,
+                    self.new_parsed_field(kind.clone(), "value".into(), value_ty, None, Vec::new())?,
+                ];
+                let name = self.define_synthesized(kind, &name, fields, None)?;
+
+                Ok(Type_::List {
+                    ty: Box::new(Type_::NonNull {
+                        ty: Box::new(Type_::NamedType { name }),
+                    }),
+                })
+            }
+            None => Err(anyhow::anyhow!(
+                "Maps are not supported (check: {}), set map_representation to map them to a scalar or a list of key/value pairs",
+                field_name
+            )),
+        }
+    }
+
+    /// Returns the type of a field declared as `keyof T`, an enum of the field names of
+    /// `T` named like `UserKeys` unless `keyof_as_enums` is disabled
+    fn parse_keyof(&mut self, field_name: &str, ty: &TsType) -> Result<Type_> {
+        if !self.options.keyof_as_enums {
+            self.check_coercion(
+                &format!("Field `{}`", field_name),
+                "a `keyof` type",
+                "String",
+            )?;
+            return Ok(Type_::NamedType {
+                name: "String".into(),
+            });
+        }
+
+        let name = match ty {
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(ident),
+                type_params: None,
+                ..
+            }) => self.resolve_import(&ident.sym).to_string(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "keyof is only supported on type references (check: {})",
+                    field_name
+                ))
+            }
+        };
+        let keys = match self.type_keys.get(&name) {
+            Some(keys) => keys.clone(),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "keyof is only supported on declared object types (check: {}): {}",
+                    field_name,
+                    name
+                ))
+            }
+        };
+
+        let enum_name = format!("{}Keys", name);
+        self.declare_enum(
+            &enum_name,
+            None,
+            keys.into_iter().map(|key| (key, DUMMY_SP)).collect(),
+        )?;
+        Ok(Type_::NamedType { name: enum_name })
+    }
+
+    /// Records the type parameters and bodies of the generic aliases declared in `items`
+    fn record_generic_aliases(&mut self, items: &[ModuleItem]) {
+        for item in items {
+            if let ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) = item {
+                if let Some(type_params) = &alias.type_params {
+                    let params = type_params
+                        .params
+                        .iter()
+                        .map(|param| param.name.sym.to_string())
+                        .collect();
+                    self.generic_aliases.insert(
+                        alias.id.sym.to_string(),
+                        (params, (*alias.type_ann).clone()),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Emits the instantiation of the manifested generic alias `name` with `args`, like
+    /// `UserConnection` for `Connection<User>`, returning the name it is referenced by
+    fn instantiate_generic(
+        &mut self,
+        field_name: &str,
+        name: &str,
+        args: &[Box<TsType>],
+    ) -> Result<String> {
+        let (params, body) = match self.generic_aliases.get(name) {
+            Some(generic) => generic.clone(),
+            None => return Err(anyhow::anyhow!("Undefined type: {}", name)),
+        };
+        if params.len() != args.len() {
+            return Err(anyhow::anyhow!(
+                "Invalid amount of type parameters for {}: {}",
+                name,
+                args.len()
+            ));
+        }
+        let kind = match self.manifest.get(name) {
+            Some(&GraphQLKind::Input) => FieldKind::Input,
+            Some(&GraphQLKind::Object) => FieldKind::Object,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Generic types must be manifested as objects or inputs (check: {})",
+                    name
+                ))
+            }
+        };
+        let parsing_inputs =
+            self.parsing_inputs || matches!(self.parent_types.last(), Some((_, FieldKind::Input)));
+        match kind {
+            FieldKind::Object if parsing_inputs => {
+                return Err(anyhow::anyhow!(
+                    "Field args can only be Inputs (check: {})",
+                    name
+                ))
+            }
+            FieldKind::Input if !parsing_inputs => {
+                return Err(anyhow::anyhow!(
+                    "Field type can't be an Input (check: {})",
+                    name
+                ))
+            }
+            _ => {}
+        }
+
+        let new_name = self.instance_name(field_name, name, args)?;
+        // Instances are synthesized, so they can't take the name of a declared type
+        if self
+            .manifest
+            .keys()
+            .any(|declared| self.graphql_type_name(declared) == new_name)
+        {
+            return Err(anyhow::anyhow!(
+                "Conflicting definitions generated for type: {}",
+                new_name
+            ));
+        }
+        let mut body = body;
+        body.visit_mut_with(&mut SubstituteTypeParams {
+            args: params
+                .into_iter()
+                .zip(args.iter().map(|arg| (**arg).clone()))
+                .collect(),
+        });
+        self.parse_type_literal(kind, &new_name, &body, None)
+    }
+
+    /// Returns the name of an instantiation of the generic alias `name`, its type
+    /// arguments followed by its own name like `UserEdgeConnection` for
+    /// `Connection<Edge<User>>`
+    fn instance_name(&self, field_name: &str, name: &str, args: &[Box<TsType>]) -> Result<String> {
+        let mut instance = String::new();
+        for arg in args {
+            match &**arg {
+                TsType::TsTypeRef(TsTypeRef {
+                    type_name: TsEntityName::Ident(ident),
+                    type_params,
+                    ..
+                }) => {
+                    let arg_name = self.resolve_import(&ident.sym).to_string();
+                    match type_params {
+                        Some(TsTypeParamInstantiation { params, .. }) => {
+                            instance.push_str(&self.instance_name(field_name, &arg_name, params)?)
+                        }
+                        None => match self.scalar_alias(&arg_name) {
+                            Some(scalar) => instance.push_str(scalar),
+                            None => instance.push_str(&self.graphql_type_name(&arg_name)),
+                        },
+                    }
+                }
+                TsType::TsKeywordType(TsKeywordType { kind, .. }) => {
+                    instance.push_str(match kind {
+                        TsKeywordTypeKind::TsStringKeyword => "String",
+                        TsKeywordTypeKind::TsNumberKeyword => "Int",
+                        TsKeywordTypeKind::TsBooleanKeyword => "Boolean",
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "Type arguments of {} must be named types (check: {})",
+                                name,
+                                field_name
+                            ))
+                        }
+                    })
+                }
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Type arguments of {} must be named types (check: {})",
+                        name,
+                        field_name
+                    ))
+                }
+            }
+        }
+        instance.push_str(&self.graphql_type_name(name));
+        Ok(instance)
+    }
+
+    /// Records the types renamed with a `@gqlName` tag before any are parsed, so
+    /// references to them use the new name regardless of declaration order
+    fn record_type_names(&mut self, items: &[ModuleItem]) -> Result<()> {
+        for item in items {
+            let (ident, span) = match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) => (&alias.id, alias.span),
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(interface))) => {
+                    (&interface.id, interface.span)
+                }
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsEnum(decl))) => (&decl.id, decl.span),
+                _ => continue,
+            };
+            // Manifested index signature types are references to the configured scalar
+            if let ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) = item {
+                if let Some(scalar) = self.index_signature_scalar(&alias.type_ann) {
+                    if self.manifest.contains_key(ident.sym.as_ref()) {
+                        self.type_names.insert(ident.sym.to_string(), scalar);
+                        continue;
+                    }
+                }
+            }
+            if let Some(name) = self.jsdoc_name(ident.sym.as_ref(), span)? {
+                self.type_names.insert(ident.sym.to_string(), name);
+            } else if self.manifest.contains_key(ident.sym.as_ref()) {
+                // Renamed like with @gqlName, so references use the legal name too
+                let name = self.legal_name(ident.sym.as_ref(), ident.sym.to_string())?;
+                if name != ident.sym.as_ref() {
+                    self.type_names.insert(ident.sym.to_string(), name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the scalar the type name `name` resolves to directly, instead of a
+    /// manifested type, from `scalar_aliases` or `BUILTIN_SCALAR_ALIASES`
+    fn scalar_alias<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        match self.options.scalar_aliases.get(name) {
+            Some(scalar) => Some(scalar),
+            None if BUILTIN_SCALAR_ALIASES.contains(&name) => Some(name),
+            None => None,
+        }
+    }
+
+    /// Records that `name` is referenced, allocating it only the first time since the
+    /// same types are referenced by many fields
+    fn reference(&mut self, name: &str) {
+        if !self.referenced.contains(name) {
+            self.referenced.insert(name.to_string());
+        }
+    }
+
+    /// Returns the name the type `ts_name` is emitted under
+    fn graphql_type_name(&self, ts_name: &str) -> String {
+        match self.type_names.get(ts_name) {
+            Some(name) => name.clone(),
+            None => ts_name.to_string(),
+        }
+    }
+
+    /// Records the member names and members of the object types declared in `items`,
+    /// which `keyof` fields and `Pick` are generated from, and their properties when
+    /// `mirror_inputs` is set
+    fn record_type_keys(&mut self, items: &[ModuleItem]) {
+        for item in items {
+            let (ident, members) = match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) => match &*alias.type_ann {
+                    TsType::TsTypeLit(lit) => (&alias.id, &lit.members),
+                    _ => continue,
+                },
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(interface))) => {
+                    (&interface.id, &interface.body.body)
+                }
+                _ => continue,
+            };
+
+            let keys = members
+                .iter()
+                .filter(|member| !self.is_ignored_member(member))
+                .filter_map(|member| match member {
+                    TsTypeElement::TsPropertySignature(TsPropertySignature {
+                        key,
+                        computed,
+                        ..
+                    })
+                    | TsTypeElement::TsMethodSignature(TsMethodSignature {
+                        key, computed, ..
+                    }) => Self::member_key(ident.sym.as_ref(), key, *computed).ok(),
+                    _ => None,
+                })
+                .collect();
+            self.type_keys.insert(ident.sym.to_string(), keys);
+            self.object_members
+                .insert(ident.sym.to_string(), members.clone());
+
+            if self.options.mirror_inputs {
+                // Fields with args are resolvers, which have no input counterpart
+                let properties = members
+                    .iter()
+                    .filter(|member| matches!(member, TsTypeElement::TsPropertySignature(_)))
+                    .cloned()
+                    .collect();
+                self.object_properties.insert(
+                    ident.sym.to_string(),
+                    TsType::TsTypeLit(TsTypeLit {
+                        span: DUMMY_SP,
+                        members: properties,
+                    }),
+                );
+            }
+        }
+    }
+
+    /// Records the values of the string literal union aliases declared in `items`, and
+    /// which manifested unions are nullable
+    fn record_unions(&mut self, items: &[ModuleItem]) -> Result<()> {
+        for item in items {
+            if let ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) = item {
+                if let TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
+                    uni,
+                )) = &*alias.type_ann
+                {
+                    let name = alias.id.sym.to_string();
+                    if let Some(values) = Self::string_literal_union(uni) {
+                        self.literal_unions.insert(name.clone(), values);
+                    }
+                    if self.manifest.get(&name) == Some(&GraphQLKind::Union)
+                        && self.is_nullable_union(&alias.type_ann)?
+                    {
+                        self.nullable_unions.insert(name);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `type_name` refers to a manifested union that includes `null`
+    fn is_nullable_union_ref(&self, type_name: &TsEntityName) -> bool {
+        match type_name {
+            TsEntityName::Ident(ident) => {
+                let name = self.resolve_import(&ident.sym);
+                self.nullable_unions.contains(name)
+            }
+            TsEntityName::TsQualifiedName(_) => false,
+        }
+    }
+
+    /// Returns the members `ty` selects as a type literal when it is `Pick<T, K>` or
+    /// `Omit<T, K>` of an object type `T`, where `K` is a union of string literals or an
+    /// alias of one. Errors on `owner` when either can't be resolved
+    fn pick_type(&self, owner: &str, ty: &TsType) -> Result<Option<TsType>> {
+        let (wrapper, type_params) = match ty {
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(ident),
+                type_params,
+                ..
+            }) if PICK_TYPES.contains(&ident.sym.as_ref()) => (ident.sym.as_ref(), type_params),
+            _ => return Ok(None),
+        };
+        let (source, keys) = match type_params {
+            Some(TsTypeParamInstantiation { params, .. }) if params.len() == 2 => {
+                (&params[0], &params[1])
+            }
+            Some(TsTypeParamInstantiation { params, .. }) => {
+                return Err(anyhow::anyhow!(
+                    "Invalid amount of type parameters for {}: {}",
+                    wrapper,
+                    params.len()
+                ))
+            }
+            None => return Err(anyhow::anyhow!("Missing type parameters for {}", wrapper)),
+        };
+
+        // The source can itself be picked, like `Omit<Pick<User, "id" | "name">, "id">`
+        let members = match self.pick_type(owner, source)? {
+            Some(TsType::TsTypeLit(lit)) => Some(lit.members),
+            _ => match &**source {
+                TsType::TsTypeRef(TsTypeRef {
+                    type_name: TsEntityName::Ident(ident),
+                    type_params: None,
+                    ..
+                }) => {
+                    let name = self.resolve_import(&ident.sym);
+                    self.object_members.get(name).cloned()
+                }
+                _ => None,
+            },
+        };
+        let members = match members {
+            Some(members) => members,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "{} is only supported over object types declared with a type literal or interface (check: {})",
+                    wrapper,
+                    owner
+                ))
+            }
+        };
+        let keys = match self.literal_union_values(keys) {
+            Some(keys) => keys,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "{} is only supported with unions of string literal keys (check: {})",
+                    wrapper,
+                    owner
+                ))
+            }
+        };
+
+        let key_of = |member: &TsTypeElement| match member {
+            TsTypeElement::TsPropertySignature(TsPropertySignature { key, computed, .. })
+            | TsTypeElement::TsMethodSignature(TsMethodSignature { key, computed, .. }) => {
+                Self::member_key(owner, key, *computed).ok()
+            }
+            _ => None,
+        };
+        // Like in Typescript, only `Pick` requires its keys to exist
+        let keep = wrapper == "Pick";
+        if keep {
+            if let Some((key, _)) = keys
+                .iter()
+                .find(|(key, _)| !members.iter().any(|m| key_of(m).as_ref() == Some(key)))
+            {
+                return Err(anyhow::anyhow!(
+                    "Pick of a type without the field `{}` (check: {})",
+                    key,
+                    owner
+                ));
+            }
+        }
+        let members = members
+            .into_iter()
+            .filter(|member| {
+                let picked = match key_of(member) {
+                    Some(member_key) => keys.iter().any(|(key, _)| *key == member_key),
+                    None => false,
+                };
+                picked == keep
+            })
+            .collect();
+        Ok(Some(TsType::TsTypeLit(TsTypeLit {
+            span: DUMMY_SP,
+            members,
+        })))
+    }
+
+    /// Returns the enum of a field declared as `Exclude<T, U>` or `Extract<T, U>`, where
+    /// both are unions of string literals or aliases of them
+    fn parse_literal_filter(
+        &mut self,
+        field_name: &str,
+        wrapper: &str,
+        type_params: &Option<TsTypeParamInstantiation>,
+    ) -> Result<Type_> {
+        let (union, filter) = match type_params {
+            Some(TsTypeParamInstantiation { params, .. }) if params.len() == 2 => {
+                (&params[0], &params[1])
+            }
+            Some(TsTypeParamInstantiation { params, .. }) => {
+                return Err(anyhow::anyhow!(
+                    "Invalid amount of type parameters for {}: {}",
+                    wrapper,
+                    params.len()
+                ))
+            }
+            None => return Err(anyhow::anyhow!("Missing type parameters for {}", wrapper)),
+        };
+        let (values, filter) = match (
+            self.literal_union_values(union),
+            self.literal_union_values(filter),
+        ) {
+            (Some(values), Some(filter)) => (values, filter),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "{} is only supported over unions of string literals (check: {})",
+                    wrapper,
+                    field_name
+                ))
+            }
+        };
+
+        // `Extract` keeps the values in the filter, `Exclude` the others
+        let keep = wrapper == "Extract";
+        let values: Vec<_> = values
+            .into_iter()
+            .filter(|(value, _)| filter.iter().any(|(f, _)| f == value) == keep)
+            .collect();
+        if values.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Field `{}` is an {} that leaves no values",
+                field_name,
+                wrapper
+            ));
+        }
+        self.parse_literal_union(field_name, values)
+    }
+
+    /// Returns the values of a string literal, a union of them or an alias of one
+    fn literal_union_values(&self, ty: &TsType) -> Option<Vec<(String, Span)>> {
+        match ty {
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(ident),
+                type_params: None,
+                ..
+            }) => {
+                let name = self.resolve_import(&ident.sym);
+                self.literal_unions.get(name).cloned()
+            }
+            TsType::TsLitType(TsLitType {
+                lit: TsLit::Str(Str { value, .. }),
+                span,
+            }) => Some(vec![(value.to_string(), *span)]),
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(uni)) => {
+                Self::string_literal_union(uni)
+            }
+            TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. }) => {
+                self.literal_union_values(type_ann)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the type of a field declared as a union of string literals, which is an
+    /// enum named after the field unless `literal_unions_as_enums` is disabled
+    fn parse_literal_union(
+        &mut self,
+        field_name: &str,
+        values: Vec<(String, Span)>,
+    ) -> Result<Type_> {
+        if !self.options.literal_unions_as_enums {
+            self.check_coercion(
+                &format!("Field `{}`", field_name),
+                "a union of string literals",
+                "String",
+            )?;
+            return Ok(Type_::NamedType {
+                name: "String".into(),
+            });
+        }
+
+        let name = self.compute_new_name(ComputeNameKind::Enum, field_name);
+        self.declare_enum(&name, None, values)?;
+        Ok(Type_::NamedType { name })
+    }
+
+    /// Emits an enum, reading the description and deprecation of each value from the
+    /// JSDoc comment at its span
+    fn declare_enum(
+        &mut self,
+        name: &str,
+        description: Option<String>,
+        values: Vec<(String, Span)>,
+    ) -> Result<()> {
+        let mut enum_def = EnumDef::new(name.to_string());
+        enum_def.description(description);
+        let mut seen = HashSet::new();
+        for (value, span) in values {
+            let value = self.legal_enum_value(name, value)?;
+            if !seen.insert(value.clone()) {
+                return Err(anyhow::anyhow!(
+                    "Duplicate value in enum {}: {}",
+                    name,
+                    value
+                ));
+            }
+            let mut enum_value = EnumValue::new(value);
+            enum_value.description(self.jsdoc_description(span));
+            if let Some(reason) = self.jsdoc_deprecation(span) {
+                enum_value.deprecated(Some(reason));
+            }
+            enum_def.value(enum_value);
+        }
+
+        if self.register_synthesized(name, enum_def.to_string())? {
+            self.definitions.push(Definition::Enum {
+                name: name.to_string(),
+                def: enum_def,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Records a type synthesized from an inline object literal, returning false if an
+    /// identical definition was already emitted. Errors if a different definition was
+    /// already emitted under the same name.
+    fn register_synthesized(&mut self, name: &str, sdl: String) -> Result<bool> {
+        match self.synthesized.get(name) {
+            Some(existing) if *existing == sdl => Ok(false),
+            Some(_) => Err(anyhow::anyhow!(
+                "Conflicting definitions generated for type: {}",
+                name
+            )),
+            None => {
+                self.synthesized.insert(name.to_string(), sdl);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Returns the names of the interfaces whose fields are all present in `fields` with a
+    /// compatible type
+    fn implemented_interfaces(&self, fields: &[ParsedField]) -> Vec<String> {
+        let signatures = fields
+            .iter()
+            .filter_map(|f| f.signature())
+            .collect::<Vec<&FieldSignature>>();
+
+        self.interfaces
+            .iter()
+            .filter(|(_, interface_fields)| {
+                interface_fields.iter().all(|expected| {
+                    signatures.iter().any(|sig| {
+                        sig.name == expected.name && Self::is_compatible_type(&sig.ty, &expected.ty)
+                    })
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// True when an object field of type `ty` can implement an interface field of type
+    /// `expected`, which allows a non-null type where the interface is nullable
+    fn is_compatible_type(ty: &Type_, expected: &Type_) -> bool {
+        match (ty, expected) {
+            (Type_::NonNull { ty }, Type_::NonNull { ty: expected }) => {
+                Self::is_compatible_type(ty, expected)
+            }
+            (Type_::NonNull { ty }, expected) => Self::is_compatible_type(ty, expected),
+            (Type_::List { ty }, Type_::List { ty: expected }) => {
+                Self::is_compatible_type(ty, expected)
+            }
+            (Type_::NamedType { name }, Type_::NamedType { name: expected }) => name == expected,
+            _ => false,
+        }
+    }
+
+    /// Adds a manifested object. The fields of a root type are merged into an earlier
+    /// declaration of the same type (e.g. `Query` declared in several files), erroring if
+    /// both declarations have a field with the same name but a different definition.
+    /// Other types can only be declared again with the same fields
+    fn define_object(
+        &mut self,
+        name: &str,
+        fields: Vec<ParsedField>,
+        directives: Vec<String>,
+    ) -> Result<()> {
+        let existing = match self.object_indices.get(name) {
+            Some(&idx) => match &mut self.definitions[idx] {
+                Definition::ManifestObject {
+                    fields, directives, ..
+                } => Some((fields, directives)),
+                _ => None,
+            },
+            None => None,
+        };
+
+        let (existing, existing_directives) = match existing {
+            Some(existing) => existing,
+            None => {
+                self.defined.insert(name.to_string(), String::new());
+                self.object_indices
+                    .insert(name.to_string(), self.definitions.len());
+                self.definitions.push(Definition::ManifestObject {
+                    name: name.to_string(),
+                    fields,
+                    directives,
+                });
+                return Ok(());
+            }
+        };
+
+        let is_root = ROOT_TYPES.contains(&name)
+            || matches!(self.manifest_entries.get(name), Some(entry) if entry.root.is_some());
+        if !is_root {
+            if *existing == fields && *existing_directives == directives {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!(
+                "Type is defined more than once with different fields: {}",
+                name
+            ));
+        }
+
+        for directive in directives {
+            if !existing_directives.contains(&directive) {
+                existing_directives.push(directive);
+            }
+        }
+
+        for field in fields {
+            let key = field.signature().map(|s| s.name.clone());
+            match existing
+                .iter()
+                .find(|f| f.signature().map(|s| s.name.clone()) == key)
+            {
+                Some(f) if *f == field => {}
+                Some(_) => {
+                    return Err(anyhow::anyhow!(
+                        "Conflicting definitions for field: {}.{}",
+                        name,
+                        key.unwrap_or_default()
+                    ))
+                }
+                None => existing.push(field),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records a manifested input or interface, returning false if an identical definition
+    /// was already emitted (e.g. the same type declared in two modules). Errors if a
+    /// different definition was already emitted under the same name.
+    fn register_defined(&mut self, name: &str, sdl: String) -> Result<bool> {
+        match self.defined.get(name) {
+            Some(existing) if *existing == sdl => Ok(false),
+            Some(_) => Err(anyhow::anyhow!(
+                "Type is defined more than once with different fields: {}",
+                name
+            )),
+            None => {
+                self.defined.insert(name.to_string(), sdl);
+                Ok(true)
+            }
+        }
+    }
+
+    fn parse_keyword_type(&mut self, field_name: &str, kind: &TsKeywordTypeKind) -> Result<Type_> {
+        match kind {
+            TsKeywordTypeKind::TsNumberKeyword => Ok(Type_::NamedType { name: "Int".into() }),
+            TsKeywordTypeKind::TsStringKeyword => Ok(Type_::NamedType {
+                name: "String".into(),
+            }),
+            TsKeywordTypeKind::TsBooleanKeyword => Ok(Type_::NamedType {
+                name: "Boolean".into(),
+            }),
+            TsKeywordTypeKind::TsBigIntKeyword => {
+                let name = self.options.bigint_scalar.clone();
+                self.declare_scalar(&name);
+                Ok(Type_::NamedType { name })
+            }
+            TsKeywordTypeKind::TsAnyKeyword | TsKeywordTypeKind::TsUnknownKeyword => {
+                match self.options.any_scalar.clone() {
+                    Some(name) => {
+                        let keyword = match kind {
+                            TsKeywordTypeKind::TsAnyKeyword => "`any`",
+                            _ => "`unknown`",
+                        };
+                        self.check_coercion(&format!("Field `{}`", field_name), keyword, &name)?;
+                        self.declare_scalar(&name);
+                        Ok(Type_::NamedType { name })
+                    }
+                    None => Err(anyhow::anyhow!(
+                        "Field `{}` has type `{}`, annotate it with a concrete type or configure a fallback scalar (any_scalar)",
+                        field_name,
+                        if let TsKeywordTypeKind::TsAnyKeyword = kind { "any" } else { "unknown" }
+                    )),
+                }
+            }
+            // Usually left over from exhaustive conditional types
+            TsKeywordTypeKind::TsNeverKeyword => Err(anyhow::anyhow!(
+                "Field `{}` resolves to `never`, which has no GraphQL representation",
+                field_name
+            )),
+            kind => Err(anyhow::anyhow!(
+                "Field `{}` has type `{}`, which has no GraphQL representation",
+                field_name,
+                match kind {
+                    TsKeywordTypeKind::TsObjectKeyword => "object",
+                    TsKeywordTypeKind::TsSymbolKeyword => "symbol",
+                    TsKeywordTypeKind::TsUndefinedKeyword => "undefined",
+                    TsKeywordTypeKind::TsNullKeyword => "null",
+                    TsKeywordTypeKind::TsVoidKeyword => "void",
+                    _ => "intrinsic",
+                }
+            )),
+        }
+    }
+
+    /// Names the kind of a type for error messages, like "a tuple type"
+    fn describe_type(ty: &TsType) -> &'static str {
+        match ty {
+            TsType::TsKeywordType(_) => "a keyword type",
+            TsType::TsThisType(_) => "type `this`",
+            TsType::TsFnOrConstructorType(TsFnOrConstructorType::TsFnType(_)) => "a function type",
+            TsType::TsFnOrConstructorType(_) => "a constructor type",
+            TsType::TsTypeRef(_) => "a type reference",
+            TsType::TsTypeQuery(_) => "a `typeof` type",
+            TsType::TsTypeLit(_) => "an object type",
+            TsType::TsArrayType(_) => "an array type",
+            TsType::TsTupleType(_) => "a tuple type",
+            TsType::TsOptionalType(_) => "an optional type",
+            TsType::TsRestType(_) => "a rest type",
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(_)) => {
+                "a union type"
+            }
+            TsType::TsUnionOrIntersectionType(_) => "an intersection type",
+            TsType::TsConditionalType(_) => "a conditional type",
+            TsType::TsInferType(_) => "an `infer` type",
+            TsType::TsParenthesizedType(_) => "a parenthesized type",
+            TsType::TsTypeOperator(_) => "a type operator",
+            TsType::TsIndexedAccessType(_) => "an indexed access type",
+            TsType::TsMappedType(_) => "a mapped type",
+            TsType::TsLitType(_) => "a literal type",
+            TsType::TsTypePredicate(_) => "a type predicate",
+            TsType::TsImportType(_) => "an import type",
+        }
+    }
+
+    /// Errors in `strict` mode instead of coercing `subject`, which is `ts_type`, into
+    /// the lossy GraphQL type `graphql_type`
+    fn check_coercion(&self, subject: &str, ts_type: &str, graphql_type: &str) -> Result<()> {
+        if !self.options.strict {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "{} is {}, which strict mode doesn't coerce to {}",
+            subject,
+            ts_type,
+            graphql_type
+        ))
+    }
+
+    /// Emits a `scalar` definition for `name`, unless it has already been emitted or is
+    /// a built-in scalar, which fallbacks like `any_scalar` can be mapped to
+    fn declare_scalar(&mut self, name: &str) {
+        if BUILTIN_SCALARS.contains(&name) {
+            return;
+        }
+        if self.declared_scalars.insert(name.to_string()) {
+            self.definitions.push(Definition::Scalar {
+                name: name.to_string(),
+            });
+        }
+    }
+
+    /// Returns the schema, the `extend type` blocks that follow it and the directives of
+    /// each type, which `apollo_encoder` can't encode. Errors if it references types that
+    /// were never defined
+    fn into_schema(mut self) -> Result<(Schema, Vec<String>, HashMap<String, Directives>)> {
+        let schema_def = self.check_manifest_entries()?;
+
+        let mut dangling = self
+            .referenced
+            .iter()
+            .filter(|name| {
+                !self.defined.contains_key(*name)
+                    && !self.declared_scalars.contains(*name)
+                    && !self.synthesized.contains_key(*name)
+            })
+            .map(|name| name.as_str())
+            .collect::<Vec<&str>>();
+
+        if !dangling.is_empty() {
+            dangling.sort_unstable();
+            return Err(anyhow::anyhow!(
+                "Types referenced but never declared: {}",
+                dangling.join(", ")
+            ));
+        }
+
+        let mut schema = Schema::new();
+        if let Some(schema_def) = schema_def {
+            schema.schema(schema_def);
+        }
+        let mut extensions = Vec::new();
+        let mut directives: HashMap<String, Directives> = HashMap::new();
+        for def in std::mem::take(&mut self.definitions) {
+            // Directives of the manifest entry come first, extensions only have fields
+            let mut type_directives = Directives {
+                on_type: match def {
+                    Definition::Extension { .. } => Vec::new(),
+                    _ => self.type_directives(def.name()),
+                },
+                on_fields: Vec::new(),
+            };
+            match &def {
+                Definition::ManifestObject {
+                    fields, directives, ..
+                } => {
+                    type_directives.on_type.extend(directives.iter().cloned());
+                    type_directives.on_fields = Directives::of_fields(fields).on_fields;
+                }
+                Definition::Object { fields, .. } | Definition::Extension { fields, .. } => {
+                    type_directives.on_fields = Directives::of_fields(fields).on_fields;
+                }
+                Definition::Input { directives, .. } | Definition::Interface { directives, .. } => {
+                    type_directives
+                        .on_type
+                        .extend(directives.on_type.iter().cloned());
+                    type_directives.on_fields = directives.on_fields.clone();
+                }
+                _ => {}
+            }
+            if !type_directives.is_empty() {
+                let entry = directives.entry(def.name().to_string()).or_default();
+                entry.on_type.extend(type_directives.on_type);
+                entry.on_fields.extend(type_directives.on_fields);
+            }
+
+            match def {
+                Definition::ManifestObject { name, fields, .. } => {
+                    let mut interfaces = if self.options.auto_implements {
+                        self.implemented_interfaces(&fields)
+                    } else {
+                        Vec::new()
+                    };
+                    if let Some(entry) = self.manifest_entries.get(&name) {
+                        for interface in &entry.extends {
+                            if !interfaces.contains(interface) {
+                                interfaces.push(interface.clone());
+                            }
+                        }
+                    }
+                    schema.object(Self::object_def(name, fields, &interfaces));
+                }
+                Definition::Object {
+                    name,
+                    description,
+                    fields,
+                } => {
+                    let mut object_def = Self::object_def(name, fields, &[]);
+                    object_def.description(description);
+                    schema.object(object_def)
+                }
+                Definition::Input { def, .. } => schema.input(def),
+                Definition::Interface { def, .. } => schema.interface(def),
+                Definition::Union { def, .. } => schema.union(def),
+                Definition::Enum { def, .. } => schema.enum_(def),
+                Definition::Scalar { name } => schema.scalar(ScalarDef::new(name)),
+                Definition::Extension { name, fields } => {
+                    extensions.push(format!("extend {}", Self::object_def(name, fields, &[])))
+                }
+            }
+        }
+
+        Ok((schema, extensions, directives))
+    }
+
+    /// Validates the per-type settings of the manifest, recording the types they
+    /// reference, and returns the `schema` definition of the root types if there are any
+    fn check_manifest_entries(&mut self) -> Result<Option<SchemaDef>> {
+        let mut entries = self.manifest_entries.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(name, _)| *name);
+
+        let mut roots = Vec::new();
+        for (name, entry) in entries {
+            if entry.kind != GraphQLKind::Object
+                && (entry.root.is_some() || !entry.extends.is_empty())
+            {
+                return Err(anyhow::anyhow!(
+                    "Only objects can be root types or implement interfaces (check: {})",
+                    name
+                ));
+            }
+            if let Some(directive) = entry
+                .directives
+                .iter()
+                .find(|directive| !Self::is_valid_directive(directive))
+            {
+                return Err(anyhow::anyhow!(
+                    "Invalid directive, expected `name` or `name(args)` (check: {}): {}",
+                    name,
+                    directive
+                ));
+            }
+            self.referenced.extend(entry.extends.iter().cloned());
+            if let Some(operation) = entry.root {
+                if let Some((_, existing)) = roots.iter().find(|(op, _)| *op == operation) {
+                    return Err(anyhow::anyhow!(
+                        "Both {} and {} are manifested as the {:?} root type",
+                        existing,
+                        name,
+                        operation
+                    ));
+                }
+                self.referenced.insert(name.clone());
+                roots.push((operation, name.clone()));
+            }
+        }
+
+        if roots.is_empty() {
+            return Ok(None);
+        }
+        roots.sort_unstable();
+        let mut schema_def = SchemaDef::new();
+        for (operation, name) in roots {
+            match operation {
+                RootOperation::Query => schema_def.query(name),
+                RootOperation::Mutation => schema_def.mutation(name),
+                RootOperation::Subscription => schema_def.subscription(name),
+            }
+        }
+        Ok(Some(schema_def))
+    }
+
+    /// Checks that `definition` is a single directive definition, returning it as a line
+    fn directive_definition(definition: &str) -> Result<String> {
+        let tree = Parser::new(definition).parse();
+        let valid = tree.errors().next().is_none();
+        let mut definitions = tree.document().definitions();
+        match (valid, definitions.next(), definitions.next()) {
+            (true, Some(ast::Definition::DirectiveDefinition(_)), None) => {
+                Ok(format!("{}\n", definition.trim()))
+            }
+            _ => Err(anyhow::anyhow!(
+                "Invalid directive definition, expected `directive @name(args) on LOCATIONS`: {}",
+                definition
+            )),
+        }
+    }
+
+    /// Returns the directives of the manifest entry of `name`
+    fn type_directives(&self, name: &str) -> Vec<String> {
+        match self.manifest_entries.get(name) {
+            Some(entry) => entry.directives.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    fn object_def(name: String, fields: Vec<ParsedField>, interfaces: &[String]) -> ObjectDef {
+        let mut object_def = ObjectDef::new(name);
+        interfaces
+            .iter()
+            .for_each(|name| object_def.interface(name.clone()));
+        fields
+            .into_iter()
+            .for_each(|f| object_def.field(f.object().unwrap()));
+        object_def
+    }
+
+    /// Returns an empty context with the same configuration, manifest and imports, used
+    /// to parse a single declaration on its own
+    fn child(&self) -> Self {
+        Self {
+            type_keys: self.type_keys.clone(),
+            literal_unions: self.literal_unions.clone(),
+            nullable_unions: self.nullable_unions.clone(),
+            generic_aliases: self.generic_aliases.clone(),
+            const_enums: self.const_enums.clone(),
+            object_properties: self.object_properties.clone(),
+            object_members: self.object_members.clone(),
+            type_names: self.type_names.clone(),
+            manifest_entries: self.manifest_entries.clone(),
+            imports: self.imports.clone(),
+            comments: self.comments.clone(),
+            ..Self::new(self.manifest.clone(), self.options.clone())
+        }
+    }
+
+    fn into_parsed_decl(self) -> ParsedDecl {
+        ParsedDecl {
+            definitions: self.definitions,
+            synthesized: self.synthesized,
+            defined: self.defined,
+            referenced: self.referenced,
+            interfaces: self.interfaces,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    /// Adds the output of a declaration parsed by a `child` context, with the same
+    /// merging and conflict checks as parsing it here
+    fn absorb(&mut self, decl: ParsedDecl) -> Result<()> {
+        for def in decl.definitions {
+            let name = def.name().to_string();
+            match def {
+                Definition::ManifestObject {
+                    fields, directives, ..
+                } => self.define_object(&name, fields, directives)?,
+                Definition::Scalar { .. } => self.declare_scalar(&name),
+                def => {
+                    let new = match (decl.synthesized.get(&name), decl.defined.get(&name)) {
+                        (Some(sdl), _) => self.register_synthesized(&name, sdl.clone())?,
+                        (None, Some(sdl)) => self.register_defined(&name, sdl.clone())?,
+                        (None, None) => true,
+                    };
+                    if new {
+                        self.interfaces.extend(
+                            decl.interfaces
+                                .iter()
+                                .filter(|(interface, _)| *interface == name)
+                                .cloned(),
+                        );
+                        self.definitions.push(def);
+                    }
+                }
+            }
+        }
+        self.referenced.extend(decl.referenced);
+        self.diagnostics.extend(decl.diagnostics);
+        Ok(())
+    }
+
+    /// Like `finish`, but also returns the diagnostics collected while parsing
+    fn finish_with_diagnostics(mut self) -> Result<(String, Vec<Diagnostic>)> {
+        let mut missing = self
+            .manifest
+            .keys()
+            .filter(|name| {
+                !self.defined.contains_key(*name)
+                    && !self.synthesized.contains_key(*name)
+                    && !self.declared_scalars.contains(*name)
+                    && !self.generic_aliases.contains_key(*name)
+            })
+            .cloned()
+            .collect::<Vec<String>>();
+        missing.sort_unstable();
+
+        let mut diagnostics = std::mem::take(&mut self.diagnostics);
+        // Names are case sensitive, but an entry that only differs by case from a skipped
+        // type is most likely a typo for it
+        let mismatches = missing
+            .into_iter()
+            .map(|name| {
+                let declared = diagnostics.iter().find_map(|diagnostic| match diagnostic {
+                    Diagnostic::SkippedType(declared) if declared.eq_ignore_ascii_case(&name) => {
+                        Some(declared.clone())
+                    }
+                    _ => None,
+                });
+                match declared {
+                    Some(declared) => Diagnostic::ManifestCaseMismatch(name, declared),
+                    None => Diagnostic::MissingDeclaration(name),
+                }
+            })
+            .collect::<Vec<Diagnostic>>();
+        diagnostics.extend(mismatches);
+
+        Ok((self.finish()?, diagnostics))
+    }
+
+    fn finish(self) -> Result<String> {
+        let format = self.options.format.clone();
+        let type_prefix = self.options.type_prefix.clone();
+        let prologue = self.options.prologue.clone();
+        let directives = self
+            .options
+            .directive_definitions
+            .iter()
+            .map(|definition| Self::directive_definition(definition))
+            .collect::<Result<String>>()?;
+        let (schema, extensions, type_directives) = self.into_schema()?;
+        let sdl = attach_directives(&(schema.finish() + &extensions.concat()), &type_directives);
+        // Directive definitions are prefixed with the types, as their args may use them
+        let sdl = directives + &sdl;
+        let sdl = match type_prefix {
+            Some(prefix) => prefix_type_names(&sdl, &prefix),
+            None => sdl,
+        };
+        // The prologue is written as is, after prefixing so its names are left alone
+        let sdl = prologue
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect::<String>()
+            + &sdl;
+        Ok(match format {
+            Some(opts) => format_sdl(&sdl, &opts),
+            None => sdl,
+        })
+    }
+}
+
+impl CodeGenCtx {
+    /// Returns true if `ty` makes a field nullable according to `null_policy` and
+    /// `undefined_policy`, erroring if either rejects it
+    fn is_nullable(&self, ty: &TsType) -> Result<bool> {
+        match ty {
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(_)) => {
+                self.is_nullable_union(ty)
+            }
+            TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. }) => {
+                self.is_nullable(type_ann)
+            }
+            TsType::TsKeywordType(TsKeywordType { kind, .. }) => {
+                let (keyword, policy) = match kind {
+                    TsKeywordTypeKind::TsNullKeyword => ("null", self.options.null_policy),
+                    TsKeywordTypeKind::TsUndefinedKeyword => {
+                        ("undefined", self.options.undefined_policy)
+                    }
+                    _ => return Ok(false),
+                };
+                match policy {
+                    NullishPolicy::Nullable => Ok(true),
+                    NullishPolicy::Ignore => Ok(false),
+                    NullishPolicy::Reject => Err(anyhow::anyhow!(
+                        "`{}` is not allowed in types by the configured nullability policy",
+                        keyword
+                    )),
+                }
+            }
+            // For now just assume type references and others are non-null
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns `ty` without the parentheses around it, like `User` for `(User)`, which
+    /// only group types and never change their meaning
+    fn unparenthesized(mut ty: &TsType) -> &TsType {
+        while let TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. }) = ty {
+            ty = type_ann;
+        }
+        ty
+    }
+
+    /// Returns true if `ty` is `null` or `undefined`, or a union including them
+    fn is_nullish(ty: &TsType) -> bool {
+        match ty {
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(_)) => {
+                Self::is_nullish_union(ty)
+            }
+            TsType::TsParenthesizedType(TsParenthesizedType { type_ann, .. }) => {
+                Self::is_nullish(type_ann)
+            }
+            TsType::TsKeywordType(TsKeywordType { kind, .. }) => matches!(
+                kind,
+                TsKeywordTypeKind::TsNullKeyword | TsKeywordTypeKind::TsUndefinedKeyword
+            ),
+            _ => false,
+        }
+    }
+
+    /// Strips every `NonNull` wrapper from a type, including those of list elements
+    fn into_nullable(ty: Type_) -> Type_ {
+        match ty {
+            Type_::NonNull { ty } => Self::into_nullable(*ty),
+            Type_::List { ty } => Type_::List {
+                ty: Box::new(Self::into_nullable(*ty)),
+            },
+            ty => ty,
+        }
+    }
+
+    /// Returns the scalar configured for index signature types when `ty` is one
+    fn index_signature_scalar(&self, ty: &TsType) -> Option<String> {
+        match &self.options.index_signature_scalar {
+            Some(scalar) if Self::has_index_signature(ty) => Some(scalar.clone()),
+            _ => None,
+        }
+    }
+
+    /// Return true if type is a type literal with an index signature, like:
+    /// `{ [key: string]: number }`
+    fn has_index_signature(ty: &TsType) -> bool {
+        match ty {
+            TsType::TsTypeLit(lit) => lit
+                .members
+                .iter()
+                .any(|m| matches!(m, TsTypeElement::TsIndexSignature(_))),
+            _ => false,
+        }
+    }
+
+    /// Returns the type literal of a field's params when it is declared as a single
+    /// object literal param, like: `(args: { id: string }) => ...`, and whether the param
+    /// is optional like `(args?: { id: string })`
+    fn object_literal_param(params: &[TsFnParam]) -> Option<(&TsTypeLit, bool)> {
+        match params {
+            [TsFnParam::Ident(BindingIdent {
+                id,
+                type_ann: Some(TsTypeAnn { type_ann, .. }),
+            })] => match &**type_ann {
+                TsType::TsTypeLit(lit) => Some((lit, id.optional)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Return true if type is like: `T | null or T | undefined`
+    fn is_nullable_union(&self, ty: &TsType) -> Result<bool> {
+        match ty {
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
+                TsUnionType { types, .. },
+            )) => {
+                let mut nullable = false;
+                for ty in types {
+                    nullable |= self.is_nullable(ty)?;
+                }
+                Ok(nullable)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn is_nullish_union(ty: &TsType) -> bool {
+        match ty {
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
+                TsUnionType { types, .. },
+            )) => types.iter().any(|ty| Self::is_nullish(ty)),
+            _ => false,
+        }
+    }
+
+    /// Returns the values of a union made up of only string literals, ignoring nullable
+    /// members.
+    ///
+    /// ```
+    /// Ex: "'open' | 'closed' | null"  -> Some(["open", "closed"])
+    ///     "'open' | number"           -> None
+    /// ```
+    fn string_literal_union(ty: &TsUnionType) -> Option<Vec<(String, Span)>> {
+        let mut values = Vec::new();
+        for t in ty.types.iter().filter(|t| !Self::is_nullish(t)) {
+            match Self::unparenthesized(t) {
+                TsType::TsLitType(TsLitType {
+                    lit: TsLit::Str(Str { value, .. }),
+                    span,
+                }) => values.push((value.to_string(), *span)),
+                _ => return None,
+            }
+        }
+
+        if values.is_empty() {
+            None
+        } else {
+            Some(values)
+        }
+    }
+
+    /// Returns the scalar of a union made up of only number literals, ignoring nullable
+    /// members: `Float` when any of them has a fraction and `Int` otherwise
+    fn number_literal_union(ty: &TsUnionType) -> Option<&'static str> {
+        let mut scalar = None;
+        for t in ty.types.iter().filter(|t| !Self::is_nullish(t)) {
+            match Self::unparenthesized(t) {
+                TsType::TsLitType(TsLitType {
+                    lit: TsLit::Number(Number { value, .. }),
+                    ..
+                }) if value.fract() == 0.0 => scalar = scalar.or(Some("Int")),
+                TsType::TsLitType(TsLitType {
+                    lit: TsLit::Number(_),
+                    ..
+                }) => scalar = Some("Float"),
+                _ => return None,
+            }
+        }
+        scalar
+    }
+
+    /// Return the first non-nullable type of a union. This will error if there is no
+    /// nullable type present.
+    ///
+    /// ```
+    /// Ex: "User | null"          -> User
+    ///     "User | string"        -> Error
+    /// ```
+    fn unwrap_union(ty: &TsUnionType) -> Result<&TsType> {
+        let mut non_null = ty.types.iter().filter(|t| !Self::is_nullish(t));
+        match (non_null.next(), non_null.count()) {
+            (None, _) => Err(anyhow::anyhow!("No non-nullable type found in union")),
+            (Some(t), 0) => Ok(t),
+            (Some(_), rest) => Err(anyhow::anyhow!(
+                "Unions of multiple non-null types are not supported, found {}",
+                rest + 1
+            )),
+        }
+    }
+
+    /// Computes a name for a new Input type. The resulting name depends on the variant of
+    /// `ComputeNameKind`. If it is `ComputeNameKind::Input` we try to generate a unique
+    /// input name based on `field_name` and `param_name`
+    ///
+    /// Otherwise, we also concatenate the name of the param
+    ///
+    /// Enums are also prefixed with the name of the type whose field is being parsed, as
+    /// fields like `status` are often declared on several types (`UserStatusEnum`).
+    ///
+    /// By default an inline object that is the only arg of a field is named without the
+    /// param name (`FindUserInput`), and with it otherwise (`FindUserInputUser`). When
+    /// `always_suffix_input_names` is set the param name is always appended.
+    fn compute_new_name(&self, kind: ComputeNameKind, field_name: &str) -> String {
+        let (prefix, suffix, param_name) = match kind {
+            ComputeNameKind::Output => ("", self.options.output_suffix.as_str(), ""),
+            ComputeNameKind::Enum => match self.parent_types.last() {
+                Some((parent, _)) => (parent.as_str(), "Enum", ""),
+                None => ("", "Enum", ""),
+            },
+            ComputeNameKind::Input(_param_name, 1) if !self.options.always_suffix_input_names => {
+                ("", self.options.input_suffix.as_str(), "")
+            }
+            ComputeNameKind::Input(param_name, _) => {
+                ("", self.options.input_suffix.as_str(), param_name)
+            }
+        };
+        // Written into a single buffer, this runs for every inline object
+        let mut name = String::with_capacity(
+            prefix.len() + field_name.len() + suffix.len() + param_name.len(),
+        );
+        name.push_str(prefix);
+        push_upper_camel_case(&mut name, field_name);
+        name.push_str(suffix);
+        push_upper_camel_case(&mut name, param_name);
+        // Field names are only checked once their type is parsed
+        if self.options.sanitize_names && !Self::is_valid_name(&name) {
+            Self::sanitize_name(&name)
+        } else {
+            name
+        }
+    }
+}
+
+fn upper_camel_case(s: &str) -> String {
+    let mut camel = String::with_capacity(s.len());
+    push_upper_camel_case(&mut camel, s);
+    camel
+}
+
+/// Appends `s` to `buf` with its first character uppercased
+fn push_upper_camel_case(buf: &mut String, s: &str) {
+    let mut chars = s.chars();
+    if let Some(first) = chars.next() {
+        buf.push(first.to_ascii_uppercase());
+        buf.push_str(chars.as_str());
+    }
+}
+
+/// Syntax of the parsed source, see `TsParseConfig`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SourceSyntax {
+    Typescript,
+    Ecmascript,
+}
+
+/// Typed alternative to the JSON options taken by `parse_ts`. The default matches the
+/// options the Node binding passes: Typescript with TSX enabled
+#[derive(Clone, Debug)]
+pub struct TsParseConfig {
+    pub syntax: SourceSyntax,
+    /// Enables TSX for Typescript and JSX for Ecmascript
+    pub tsx: bool,
+    pub decorators: bool,
+    pub dynamic_import: bool,
+    pub target: JscTarget,
+    pub is_module: bool,
+    /// Collect comments, needed for JSDoc tags
+    pub comments: bool,
+}
+
+impl Default for TsParseConfig {
+    fn default() -> Self {
+        Self {
+            syntax: SourceSyntax::Typescript,
+            tsx: true,
+            decorators: false,
+            dynamic_import: false,
+            target: JscTarget::default(),
+            is_module: true,
+            comments: true,
+        }
+    }
+}
+
+impl From<TsParseConfig> for ParseOptions {
+    fn from(config: TsParseConfig) -> Self {
+        let syntax = match config.syntax {
+            SourceSyntax::Typescript => Syntax::Typescript(TsConfig {
+                tsx: config.tsx,
+                decorators: config.decorators,
+                dynamic_import: config.dynamic_import,
+                ..TsConfig::default()
+            }),
+            SourceSyntax::Ecmascript => Syntax::Es(EsConfig {
+                jsx: config.tsx,
+                decorators: config.decorators,
+                dynamic_import: config.dynamic_import,
+                ..EsConfig::default()
+            }),
+        };
+
+        ParseOptions {
+            comments: config.comments,
+            syntax,
+            is_module: config.is_module,
+            target: config.target,
+        }
+    }
+}
+
+/// Parses `s` with `opts`, a JSON string of swc's `ParseOptions`
+pub fn parse_ts(s: &str, opts: &str) -> Result<Program> {
+    parse_ts_with_comments(s, opts).map(|(program, _)| program)
+}
+
+/// Like `parse_ts`, but takes a typed config instead of a JSON string
+pub fn parse_ts_with(s: &str, config: &TsParseConfig) -> Result<Program> {
+    parse_with_options(s, config.clone().into()).map(|(program, _)| program)
+}
+
+/// Like `parse_ts`, but always collects comments and returns them with the program, so
+/// they can be passed to `generate_schema_with_comments`
+pub fn parse_ts_with_comments(s: &str, opts: &str) -> Result<(Program, SwcComments)> {
+    let opts: ParseOptions =
+        serde_json::from_str(opts).context("failed to parse the parse options")?;
+    parse_ts_with_parse_options(s, opts)
+}
+
+/// Like `parse_ts_with_comments`, but takes swc's parse options already deserialized
+pub fn parse_ts_with_parse_options(
+    s: &str,
+    mut opts: ParseOptions,
+) -> Result<(Program, SwcComments)> {
+    opts.comments = true;
+    parse_with_options(s, opts)
+}
+
+fn parse_with_options(s: &str, opts: ParseOptions) -> Result<(Program, SwcComments)> {
+    let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+    let c = Arc::new(Compiler::new(cm));
+
+    try_with_handler(c.cm.clone(), |handler| {
+        let fm = c.cm.new_source_file(FileName::Anon, s.into());
+        let program = c
+            .parse_js(
+                fm,
+                handler,
+                opts.target,
+                opts.syntax,
+                opts.is_module,
+                opts.comments,
+            )
+            .context("failed to parse code")?;
+
+        Ok((program, c.comments().clone()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::fs;
+    use std::path::Path;
+
+    fn get_prog(src: &str) -> Program {
+        get_prog_with_comments(src).0
+    }
+
+    fn get_prog_with_comments(src: &str) -> (Program, SwcComments) {
+        parse_ts_with_comments(
+            src,
+            "{
+                \"syntax\": \"typescript\",
+                \"tsx\": true,
+                \"decorators\": false,
+                \"dynamicImport\": false
+          }",
+        )
+        .unwrap()
+    }
+
+    /// Generates the schema of the fixture in `dir` from its `input.ts` (with its JSDoc
+    /// comments) and `manifest.json`, and compares it with its `expected.graphql`
+    fn run_fixture(dir: &Path) {
+        let read = |file: &str| {
+            let path = dir.join(file);
+            fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+        };
+
+        let manifest: HashMap<String, u8> = serde_json::from_str(&read("manifest.json")).unwrap();
+        let manifest = manifest
+            .into_iter()
+            .map(|(name, kind)| (name, GraphQLKind::from_u8(kind).unwrap()))
+            .collect();
+
+        let (prog, comments) = get_prog_with_comments(&read("input.ts"));
+        let out =
+            generate_schema_with_comments(prog.module().unwrap(), comments, manifest).unwrap();
+        assert_eq!(read("expected.graphql"), out, "fixture: {}", dir.display());
+    }
+
+    fn test(src: &str, expected: &str, mani: Vec<(&str, GraphQLKind)>) {
+        test_with(src, expected, mani, |_| {})
+    }
+
+    /// Like `test`, but lets the caller configure the `CodeGenCtx` before parsing
+    fn test_with(
+        src: &str,
+        expected: &str,
+        mani: Vec<(&str, GraphQLKind)>,
+        configure: impl FnOnce(&mut CodeGenCtx),
+    ) {
+        let (prog, comments) = get_prog_with_comments(src);
+
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        mani.into_iter().for_each(|(k, v)| {
+            map.insert(k.into(), v);
+        });
+
+        let mut gen = CodeGenCtx::new(map, CodeGenOptions::default());
+        gen.comments = comments;
+        configure(&mut gen);
+
+        gen.parse(prog.module().unwrap()).unwrap();
+        let out = gen.finish().unwrap();
+        println!("{}", out);
+        assert_eq!(expected, out);
+    }
+
+    /// Returns the error so callers can check its message
+    fn test_expect_err(src: &str, mani: Vec<(&str, GraphQLKind)>) -> anyhow::Error {
+        test_expect_err_with(src, mani, |_| {})
+    }
+
+    /// Like `test_expect_err`, but lets `configure` set options first. JSDoc tags are
+    /// read from the comments of `src`, and errors of `finish` are returned too
+    fn test_expect_err_with(
+        src: &str,
+        mani: Vec<(&str, GraphQLKind)>,
+        configure: impl FnOnce(&mut CodeGenCtx),
+    ) -> anyhow::Error {
+        let (prog, comments) = get_prog_with_comments(src);
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        mani.into_iter().for_each(|(k, v)| {
+            map.insert(k.into(), v);
+        });
+        let mut gen = CodeGenCtx::new(map, CodeGenOptions::default());
+        gen.comments = comments;
+        configure(&mut gen);
+        match gen.parse(prog.module().unwrap()).and_then(|_| gen.finish()) {
+            Err(e) => e,
+            Ok(out) => panic!("Expected error, got: {}", out),
+        }
+    }
+
+    #[test]
+    fn it_matches_fixtures() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        let mut dirs = fs::read_dir(root)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        dirs.sort();
+
+        assert!(!dirs.is_empty());
+        dirs.iter().for_each(|dir| run_fixture(dir));
+    }
+
+    #[test]
+    fn it_fails_when_a_field_is_an_input() {
+        // Basic
+        let src = "
+                type User = { id: string; name: string; karma: number; }
+                type AnInput = { dummy: string }
+                type Player = { user: User[]; woops: AnInput; }
+                ";
+        test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("AnInput", GraphQLKind::Input),
+                ("Player", GraphQLKind::Object),
+            ],
+        );
+
+        // Field with args
+        let src = "
+                type AnInput = { id: string; name: string; karma: number; }
+                type Query = { doSomething: (input: { whatever: string }) => Promise<AnInput> }
+                ";
+        test_expect_err(
+            src,
+            vec![
+                ("AnInput", GraphQLKind::Input),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_parses_array_fields() {
+        // Basic
+        let src = "
+        type User = { id: string; name: string; karma: number; }
+        type Player = { user: User[]; level: number[]; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+              karma: Int!
+            }
+            type Player {
+              user: [User!]!
+              level: [Int!]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Player", GraphQLKind::Object),
+            ],
+        );
+
+        // Optional
+        let src = "
+        type User = { id: string; name: string; karma: number; }
+        type Player = { user?: User[]; level?: number[]; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+              karma: Int!
+            }
+            type Player {
+              user: [User!]
+              level: [Int!]
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Player", GraphQLKind::Object),
+            ],
+        );
+
+        // Nested
+        let src = "
+        type User = { id: string[][]; name: string; karma: number; }
+        type Player = { user?: User[][]; level?: number[][]; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: [[String!]!]!
+              name: String!
+              karma: Int!
+            }
+            type Player {
+              user: [[User!]!]
+              level: [[Int!]!]
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Player", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_emits_extensions_of_root_types() {
+        let src = "
+        type User = { id: string; }
+        type Query = {
+            me: User;
+        }
+        /** @gqlExtend */
+        type Query = {
+            users: (args: { first: number }) => Promise<User[]>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Query {
+              me: User!
+            }
+            extend type Query {
+              users(first: Int!): [User!]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+
+        let src = "
+        /** @gqlExtend */
+        type User = { id: string; }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Only Query, Mutation and Subscription can be tagged with @gqlExtend (check: User)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_reads_settings_from_manifest_entries() {
+        let src = "
+        type Node = { id: string; }
+        type User = { id: string; name: string; }
+        type Status = 'active' | 'banned';
+        type RootQuery = { user: User; }
+        type RootMutation = { ban: (args: { id: string }) => Promise<Status>; }
+        ";
+        let mut manifest: HashMap<String, ManifestEntry> = HashMap::new();
+        manifest.insert("Node".into(), GraphQLKind::Interface.into());
+        manifest.insert(
+            "User".into(),
+            ManifestEntry {
+                extends: vec!["Node".into()],
+                directives: vec!["key(fields: \"id\")".into()],
+                ..GraphQLKind::Object.into()
+            },
+        );
+        manifest.insert(
+            "Status".into(),
+            ManifestEntry {
+                directives: vec!["shareable".into()],
+                ..GraphQLKind::Enum.into()
+            },
+        );
+        manifest.insert(
+            "RootQuery".into(),
+            ManifestEntry {
+                root: Some(RootOperation::Query),
+                ..GraphQLKind::Object.into()
+            },
+        );
+        manifest.insert(
+            "RootMutation".into(),
+            ManifestEntry {
+                root: Some(RootOperation::Mutation),
+                ..GraphQLKind::Object.into()
+            },
+        );
+
+        let out = generate_schema(get_prog(src).module().unwrap(), manifest).unwrap();
+        assert_eq!(
+            indoc! { r#"
+            schema {
+              query: RootQuery
+              mutation: RootMutation
+            }
+            interface Node {
+              id: String!
+            }
+            type User implements Node @key(fields: "id") {
+              id: String!
+              name: String!
+            }
+            enum Status @shareable {
+              active
+              banned
+            }
+            type RootQuery {
+              user: User!
+            }
+            type RootMutation {
+              ban(id: String!): Status!
+            }
+            "# },
+            out
+        );
+        validate_schema(&out).unwrap();
+
+        let mut manifest: HashMap<String, ManifestEntry> = HashMap::new();
+        manifest.insert(
+            "User".into(),
+            ManifestEntry {
+                extends: vec!["Node".into()],
+                ..GraphQLKind::Object.into()
+            },
+        );
+        let err = generate_schema(get_prog(src).module().unwrap(), manifest).unwrap_err();
+        assert_eq!("Types referenced but never declared: Node", err.to_string());
+
+        let mut manifest: HashMap<String, ManifestEntry> = HashMap::new();
+        manifest.insert(
+            "Status".into(),
+            ManifestEntry {
+                root: Some(RootOperation::Query),
+                ..GraphQLKind::Enum.into()
+            },
+        );
+        let err = generate_schema(get_prog(src).module().unwrap(), manifest).unwrap_err();
+        assert_eq!(
+            "Only objects can be root types or implement interfaces (check: Status)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_builds_an_extendable_schema() {
+        let src = "
+        type User = { id: string; name: string; }
+        ";
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+
+        let mut schema = build_schema(get_prog(src).module().unwrap(), map).unwrap();
+        schema.scalar(ScalarDef::new("Date".into()));
+
+        assert_eq!(
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+            }
+            scalar Date
+            "# },
+            schema.finish()
+        );
+    }
+
+    #[test]
+    fn it_parses_nested_array_nullability() {
+        let src = "
+        type User = { id: string; }
+        type Team = {
+            grid: User[][];
+            optionalGrid?: User[][];
+            nullableGrid: User[][] | null;
+            names: string[][] | undefined;
+            nullableUsers: Array<User | null>[];
+            nullableRows: Array<User[] | null>;
+            nullableRowsOfUsers: Array<Array<User | null> | null>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Team {
+              grid: [[User!]!]!
+              optionalGrid: [[User!]!]
+              nullableGrid: [[User!]!]
+              names: [[String!]!]
+              nullableUsers: [[User]!]!
+              nullableRows: [[User!]]!
+              nullableRowsOfUsers: [[User]]!
+            }
+            "# },
+            vec![("User", GraphQLKind::Object), ("Team", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_parses_readonly_fields() {
+        let mani = || vec![("User", GraphQLKind::Object), ("Team", GraphQLKind::Object)];
+        let expected = indoc! { r#"
+            type User {
+              id: String!
+              name: String
+            }
+            type Team {
+              members: [User!]!
+              tags: [[String!]!]
+            }
+            "# };
+
+        let src = "
+        type User = { id: string; name?: string; }
+        type Team = { members: User[]; tags?: string[][]; }
+        ";
+        test(src, expected, mani());
+
+        let src = "
+        type User = { readonly id: string; readonly name?: string; }
+        type Team = { readonly members: readonly User[]; tags?: readonly string[][]; }
+        ";
+        test(src, expected, mani());
+    }
+
+    #[test]
+    fn it_formats_with_four_space_indent_and_blank_lines() {
+        let src = "
+        type User = { id: string; name: string; }
+        type Player = { user: User; level: number; }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            type User {
+                id: String!
+                name: String!
+            }
+
+            type Player {
+                user: User!
+                level: Int!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Player", GraphQLKind::Object),
+            ],
+            |ctx| {
+                ctx.options.format = Some(FormatOptions {
+                    indent: 4,
+                    blank_line_between_definitions: true,
+                    trailing_newline: true,
+                })
+            },
+        );
+    }
+
+    #[test]
+    fn it_formats_without_trailing_newline() {
+        let src = "
+        type User = { id: string; name: string; }
+        type Player = { user: User; level: number; }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+            }
+            type Player {
+              user: User!
+              level: Int!
+            }"# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Player", GraphQLKind::Object),
+            ],
+            |ctx| {
+                ctx.options.format = Some(FormatOptions {
+                    trailing_newline: false,
+                    ..FormatOptions::default()
+                })
+            },
+        );
+    }
+
+    #[test]
+    fn it_keeps_descriptions_attached_when_formatting() {
+        let sdl = indoc! { r#"
+            """
+            A user
+            of the app
+            """
+            type User {
+              "The id"
+              id: String!
+            }
+            "Some scalar"
+            scalar Date
+            "# };
+        let opts = FormatOptions {
+            indent: 4,
+            blank_line_between_definitions: true,
+            trailing_newline: true,
+        };
+        assert_eq!(
+            indoc! { r#"
+            """
+            A user
+            of the app
+            """
+            type User {
+                "The id"
+                id: String!
+            }
+
+            "Some scalar"
+            scalar Date
+            "# },
+            format_sdl(sdl, &opts)
+        );
+    }
+
+    #[test]
+    fn it_parses_with_the_default_config() {
+        let src = "
+        /** @gqlDirective auth */
+        type User = { id: string; el: () => JSX.Element; }
+        ";
+        let prog = parse_ts_with(src, &TsParseConfig::default()).unwrap();
+        assert_eq!(1, prog.module().unwrap().body.len());
+
+        let opts: ParseOptions = TsParseConfig::default().into();
+        assert!(opts.comments);
+        assert!(opts.is_module);
+        assert!(matches!(
+            opts.syntax,
+            Syntax::Typescript(TsConfig { tsx: true, .. })
+        ));
+    }
+
+    #[test]
+    fn it_fails_on_malformed_parse_options() {
+        let err = parse_ts("type User = { id: string; }", "{ \"syntax\": ").unwrap_err();
+        assert_eq!("failed to parse the parse options", err.to_string());
+
+        assert!(parse_ts("type User = { id: string; }", "{ \"syntax\": \"cobol\" }").is_err());
+    }
+
+    #[test]
+    fn it_reports_skipped_and_missing_types() {
+        let src = "
+        type User = { id: string; }
+        type Helper = { value: string; }
+        ";
+        let prog = get_prog(src);
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("Users".into(), GraphQLKind::Object);
+        map.insert("Post".into(), GraphQLKind::Object);
+
+        let (out, diagnostics) = generate_schema_with_diagnostics(
+            prog.module().unwrap(),
+            map,
+            CodeGenOptions::default(),
+        )
+        .unwrap();
+        assert_eq!("", out);
+        assert_eq!(
+            vec![
+                Diagnostic::SkippedType("User".into()),
+                Diagnostic::SkippedType("Helper".into()),
+                Diagnostic::MissingDeclaration("Post".into()),
+                Diagnostic::MissingDeclaration("Users".into()),
+            ],
+            diagnostics
+        );
+        assert_eq!(
+            "manifest entry Users has no matching declaration",
+            diagnostics[3].to_string()
+        );
+    }
+
+    #[test]
+    fn it_reports_case_mismatched_manifest_entries() {
+        let src = "
+        type User = { id: string; }
+        type Post = { id: string; }
+        ";
+        let prog = get_prog(src);
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("user".into(), GraphQLKind::Object);
+        map.insert("Post".into(), GraphQLKind::Object);
+        map.insert("Comment".into(), GraphQLKind::Object);
+
+        let (out, diagnostics) = generate_schema_with_diagnostics(
+            prog.module().unwrap(),
+            map,
+            CodeGenOptions::default(),
+        )
+        .unwrap();
+        assert_eq!("type Post {\n  id: String!\n}\n", out);
+        assert_eq!(
+            vec![
+                Diagnostic::SkippedType("User".into()),
+                Diagnostic::MissingDeclaration("Comment".into()),
+                Diagnostic::ManifestCaseMismatch("user".into(), "User".into()),
+            ],
+            diagnostics
+        );
+        assert_eq!(
+            "manifest entry user has no matching declaration, did you mean User?",
+            diagnostics[2].to_string()
+        );
+    }
+
+    #[test]
+    fn it_reports_no_diagnostics_when_manifest_matches() {
+        let src = "
+        type User = { id: string; role: Role; }
+        type Role = 'ADMIN' | 'USER';
+        ";
+        let prog = get_prog(src);
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+        map.insert("Role".into(), GraphQLKind::Enum);
+
+        let (_, diagnostics) = generate_schema_with_diagnostics(
+            prog.module().unwrap(),
+            map,
+            CodeGenOptions::default(),
+        )
+        .unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn it_attaches_manifest_directives_to_types() {
+        let src = "
+        interface Node { id: string; }
+        interface Entity { id: string; }
+        type User = { id: string; }
+        type Bot = { id: string; }
+        type Result = User | Bot;
+        type UserBy = { id: string } | { email: string };
+        type Query = { user: (args: { by: UserBy }) => Promise<Result>; }
+        ";
+        let mut manifest: HashMap<String, ManifestEntry> = HashMap::new();
+        let entry = |kind: GraphQLKind, extends: Vec<&str>| ManifestEntry {
+            extends: extends.into_iter().map(String::from).collect(),
+            directives: vec!["tag(name: \"public\")".into()],
+            ..kind.into()
+        };
+        manifest.insert("Node".into(), entry(GraphQLKind::Interface, vec![]));
+        manifest.insert("Entity".into(), GraphQLKind::Interface.into());
+        manifest.insert(
+            "User".into(),
+            entry(GraphQLKind::Object, vec!["Node", "Entity"]),
+        );
+        manifest.insert("Bot".into(), GraphQLKind::Object.into());
+        manifest.insert("Result".into(), entry(GraphQLKind::Union, vec![]));
+        manifest.insert("UserBy".into(), entry(GraphQLKind::Input, vec![]));
+        manifest.insert("Query".into(), GraphQLKind::Object.into());
+
+        let out = generate_schema(get_prog(src).module().unwrap(), manifest).unwrap();
+        assert_eq!(
+            indoc! { r#"
+            interface Node @tag(name: "public") {
+              id: String!
+            }
+            interface Entity {
+              id: String!
+            }
+            type User implements Node& Entity @tag(name: "public") {
+              id: String!
+            }
+            type Bot {
+              id: String!
+            }
+            union Result @tag(name: "public") = User | Bot
+            input UserBy @tag(name: "public") @oneOf {
+              id: String
+              email: String
+            }
+            type Query {
+              user(by: UserBy!): Result!
+            }
+            "# },
+            out
+        );
+        validate_schema(&out).unwrap();
+    }
+
+    #[test]
+    fn it_attaches_jsdoc_directives() {
+        let src = "
+        type User = {
+            id: string;
+            /** @gqlDirective auth(role: ADMIN) */
+            email: string;
+            /**
+             * Internal notes
+             * @gqlDirective auth(role: ADMIN)
+             * @gqlDirective cacheControl(maxAge: 0)
+             */
+            notes(args: { limit: number }): Promise<string[]>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              email: String! @auth(role: ADMIN)
+              "Internal notes"
+              notes(limit: Int!): [String!]! @auth(role: ADMIN) @cacheControl(maxAge: 0)
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+
+        let src = "
+        type UsersInput = {
+            /**
+             * @default 10
+             * @gqlDirective auth(role: ADMIN)
+             */
+            first?: number;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            input UsersInput {
+              first: Int = 10 @auth(role: ADMIN)
+            }
+            "# },
+            vec![("UsersInput", GraphQLKind::Input)],
+        );
+    }
+
+    #[test]
+    fn it_writes_directive_definitions() {
+        let src = r#"
+        type Role = "ADMIN" | "USER";
+        type User = {
+            id: string;
+            /** @gqlDirective auth(role: ADMIN) */
+            email: string;
+        }
+        "#;
+        test_with(
+            src,
+            indoc! { r#"
+            directive @auth(role: Foo_Role = USER) on OBJECT | FIELD_DEFINITION
+            enum Foo_Role {
+              ADMIN
+              USER
+            }
+            type Foo_User {
+              id: String!
+              email: String! @auth(role: ADMIN)
+            }
+            "# },
+            vec![("Role", GraphQLKind::Enum), ("User", GraphQLKind::Object)],
+            |gen| {
+                gen.options.directive_definitions =
+                    vec!["directive @auth(role: Role = USER) on OBJECT | FIELD_DEFINITION".into()];
+                gen.options.type_prefix = Some("Foo_".into());
+            },
+        );
+
+        let err = test_expect_err_with(
+            src,
+            vec![("Role", GraphQLKind::Enum), ("User", GraphQLKind::Object)],
+            |gen| {
+                gen.options.directive_definitions =
+                    vec!["@auth(role: Role) on FIELD_DEFINITION".into()]
+            },
+        );
+        assert_eq!(
+            "Invalid directive definition, expected `directive @name(args) on LOCATIONS`: @auth(role: Role) on FIELD_DEFINITION",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_emits_federation_field_directives() {
+        let src = "
+        type Shipping = { cost: number; }
+        type Product = {
+            upc: string;
+            /** @gqlExternal */
+            weight: number;
+            /**
+             * @gqlExternal
+             */
+            size: number;
+            /**
+             * @gqlRequires size weight
+             */
+            shippingEstimate(zip: string): Promise<number>;
+            /**
+             * Fastest option
+             * @gqlProvides cost
+             */
+            shipping: Shipping;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type Shipping {
+              cost: Int!
+            }
+            type Product {
+              upc: String!
+              weight: Int! @external
+              size: Int! @external
+              shippingEstimate(zip: String!): Int! @requires(fields: "size weight")
+              "Fastest option"
+              shipping: Shipping! @provides(fields: "cost")
+            }
+            "# },
+            vec![
+                ("Shipping", GraphQLKind::Object),
+                ("Product", GraphQLKind::Object),
+            ],
+        );
+
+        // Directives of extension fields and of fields that implement an interface
+        let src = "
+        interface Priced { price: number; }
+        type Product = {
+            upc: string;
+            /** @gqlExternal */
+            price: number;
+        }
+        type Query = { product: Product; }
+        /** @gqlExtend */
+        type Query = {
+            /** @gqlProvides price */
+            topProducts: Product[];
+        }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            interface Priced {
+              price: Int!
+            }
+            type Product implements Priced {
+              upc: String!
+              price: Int! @external
+            }
+            type Query {
+              product: Product!
+            }
+            extend type Query {
+              topProducts: [Product!]! @provides(fields: "price")
+            }
+            "# },
+            vec![
+                ("Priced", GraphQLKind::Interface),
+                ("Product", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+            |gen| gen.options.auto_implements = true,
+        );
+
+        let err = test_expect_err(
+            "
+            type ProductInput = {
+                /**
+                 * @gqlExternal
+                 */
+                weight: number;
+            }
+            ",
+            vec![("ProductInput", GraphQLKind::Input)],
+        );
+        assert_eq!(
+            "@gqlExternal only applies to object fields (check: weight)",
+            err.to_string()
+        );
+
+        let err = test_expect_err(
+            "
+            type Product = {
+                /**
+                 * @gqlRequires
+                 */
+                estimate: number;
+            }
+            ",
+            vec![("Product", GraphQLKind::Object)],
+        );
+        assert_eq!(
+            "Invalid @gqlRequires, expected a field set (check: estimate)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_fails_on_malformed_directives() {
+        let src = "
+        type User = {
+            /** @gqlDirective 1auth(role: ADMIN) */
+            email: string;
+        }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Invalid directive, expected `name` or `name(args)` (check: email): 1auth(role: ADMIN)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_emits_implements_for_structural_interfaces() {
+        let src = "
+        type Admin = { id: string; permissions: string[]; }
+        type Named = { name?: string; }
+        type Ranked = { name: number; }
+        type Node = { id: string; }
+        type Profile = { name: string; bio: string; }
+        type Post = { title: string; }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            interface Named {
+              name: String
+            }
+            interface Ranked {
+              name: Int!
+            }
+            interface Node {
+              id: String!
+            }
+            type Admin implements Node {
+              id: String!
+              permissions: [String!]!
+            }
+            type Profile implements Named {
+              name: String!
+              bio: String!
+            }
+            type Post {
+              title: String!
+            }
+            "# },
+            vec![
+                ("Admin", GraphQLKind::Object),
+                ("Named", GraphQLKind::Interface),
+                ("Ranked", GraphQLKind::Interface),
+                ("Node", GraphQLKind::Interface),
+                ("Profile", GraphQLKind::Object),
+                ("Post", GraphQLKind::Object),
+            ],
+            |ctx| ctx.options.auto_implements = true,
+        );
+    }
+
+    #[test]
+    fn it_merges_repeated_object_declarations() {
+        let src = "
+        type Query = { me: () => Promise<User>; }
+        type User = { id: string; }
+        type Query = { user: (args: { id: string }) => Promise<User | null>; me: () => Promise<User>; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type Query {
+              me: User!
+              user(id: String!): User
+            }
+            type User {
+              id: String!
+            }
+            "# },
+            vec![
+                ("Query", GraphQLKind::Object),
+                ("User", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_fails_on_conflicting_merged_fields() {
+        let src = "
+        type Query = { me: () => Promise<User>; }
+        type User = { id: string; }
+        type Query = { me: () => Promise<User | null>; }
+        ";
+        let err = test_expect_err(
+            src,
+            vec![
+                ("Query", GraphQLKind::Object),
+                ("User", GraphQLKind::Object),
+            ],
+        );
+        assert_eq!(
+            "Conflicting definitions for field: Query.me",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_returns_ids_from_mutations() {
+        let src = "
+        type ID = string;
+        type User = {
+            /** @gqlID */
+            id: string;
+            /** @gqlID */
+            friendIds?: number[] | null;
+        }
+        type Mutation = {
+            /** @gqlID */
+            createUser: (args: { name: string }) => Promise<string>;
+            deleteUser: (args: { id: ID }) => Promise<ID | null>;
+            /** @gqlID */
+            archiveUsers(ids: ID[]): Promise<string[]>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: ID!
+              friendIds: [ID!]
+            }
+            type Mutation {
+              createUser(name: String!): ID!
+              deleteUser(id: ID!): ID
+              archiveUsers(ids: [ID!]!): [ID!]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Mutation", GraphQLKind::Object),
+            ],
+        );
+
+        let err = test_expect_err(
+            "
+            type User = {
+                /** @gqlID */
+                active: boolean;
+            }
+            ",
+            vec![("User", GraphQLKind::Object)],
+        );
+        assert_eq!(
+            "Only string and number fields can be tagged with @gqlID (check: active): Boolean",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_renames_fields_and_types() {
+        let src = "
+        /** @gqlName Account */
+        type User = {
+            /** @gqlName publicId */
+            internalId: string;
+            /**
+             * The friends of the user
+             * @gqlName friends
+             */
+            friendList(first: number): User[];
+        }
+        type Query = {
+            me: User;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type Account {
+              publicId: String!
+              "The friends of the user"
+              friends(first: Int!): [Account!]
+            }
+            type Query {
+              me: Account!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_ignores_tagged_fields_and_args() {
+        let src = "
+        type User = {
+            id: string;
+            /** @gqlIgnore */
+            passwordHash: string;
+            posts(first: number, /** @gqlIgnore */ ctx: string): string[];
+            friends(args: {
+                first: number;
+                /** @gqlIgnore */
+                internal?: boolean;
+            }): Promise<User[]>;
+            /** @gqlIgnore */
+            resolveSecret(): string;
+        }
+        type Secret = {
+            /** @gqlIgnore */
+            value: string;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              posts(first: Int!): [String!]
+              friends(first: Int!): [User!]!
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+
+        let err = test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Secret", GraphQLKind::Object),
+            ],
+        );
+        assert_eq!(
+            "Every field of type Secret is ignored with @gqlIgnore, types must have at least one field",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_emits_one_of_inputs_from_unions() {
+        let src = "
+        type UserBy = { byId: string } | { byName: string } | ({ byEmails: string[] });
+        type Query = {
+            user: (args: { by: UserBy }) => Promise<string>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            input UserBy @oneOf {
+              byId: String
+              byName: String
+              byEmails: [String!]
+            }
+            type Query {
+              user(by: UserBy!): String!
+            }
+            "# },
+            vec![
+                ("UserBy", GraphQLKind::Input),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+
+        let src = "
+        type UserBy = { byId: string } | { byName: string; exact: boolean };
+        ";
+        let err = test_expect_err(src, vec![("UserBy", GraphQLKind::Input)]);
+        assert_eq!(
+            "Members of a @oneOf input must have exactly one field (check: UserBy)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_emits_cyclic_inputs_once() {
+        let src = "
+        type AInput = {
+            b?: BInput;
+            nested?: { b?: BInput; a?: AInput };
+        }
+        type BInput = {
+            a?: AInput;
+            self?: BInput[];
+        }
+        type Query = {
+            find: (args: { a: AInput; b?: BInput }) => Promise<string>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            input AInputNested {
+              b: BInput
+              a: AInput
+            }
+            input AInput {
+              b: BInput
+              nested: AInputNested
+            }
+            input BInput {
+              a: AInput
+              self: [BInput!]
+            }
+            type Query {
+              find(a: AInput!, b: BInput): String!
+            }
+            "# },
+            vec![
+                ("AInput", GraphQLKind::Input),
+                ("BInput", GraphQLKind::Input),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_reads_defaults_from_jsdoc() {
+        let src = r#"
+        type UserFilter = {
+            /** @default true */
+            active?: boolean;
+            /**
+             * Sort direction
+             * @default "asc"
+             */
+            order?: string;
+            name?: string;
+        }
+        type Query = {
+            users: (args: {
+                filter?: UserFilter;
+                /** @default 10 */
+                first?: number;
+            }) => Promise<string[]>;
+            search(/** @default "" */ term: string, /** @default */ limit?: number): Promise<string[]>;
+        }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            input UserFilter {
+              active: Boolean = true
+              "Sort direction"
+              order: String = "asc"
+              name: String
+            }
+            type Query {
+              users(filter: UserFilter, first: Int! = 10): [String!]!
+              search(term: String! = "", limit: Int): [String!]!
+            }
+            "# },
+            vec![
+                ("UserFilter", GraphQLKind::Input),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_orders_args() {
+        let src = r#"
+        type Query = {
+            users(
+                sort?: string,
+                first: number,
+                /** @default 0 */ offset?: number,
+                after?: string,
+                filter: string
+            ): Promise<string[]>;
+        }
+        "#;
+        let cases = [
+            (
+                ArgOrder::Source,
+                "users(sort: String, first: Int!, offset: Int! = 0, after: String, filter: String!): [String!]!",
+            ),
+            (
+                ArgOrder::Alphabetical,
+                "users(after: String, filter: String!, first: Int!, offset: Int! = 0, sort: String): [String!]!",
+            ),
+            (
+                ArgOrder::RequiredFirst,
+                "users(first: Int!, filter: String!, sort: String, offset: Int! = 0, after: String): [String!]!",
+            ),
+        ];
+        for (order, field) in cases.iter() {
+            test_with(
+                src,
+                &format!("type Query {{\n  {}\n}}\n", field),
+                vec![("Query", GraphQLKind::Object)],
+                |gen| gen.options.arg_order = *order,
+            );
+        }
+    }
+
+    #[test]
+    fn it_overrides_nullability_with_tags() {
+        let src = r#"
+        type User = {
+            /** @gqlNonNull */
+            name?: string;
+            /** @gqlNonNull */
+            email: string | null;
+            /** @gqlNullable */
+            tags: string[];
+            /** @gqlNullable */
+            friends(first: number): Promise<User[]>;
+            /** @gqlNullable */
+            bio?: string;
+        }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              name: String!
+              email: String!
+              tags: [String!]
+              friends(first: Int!): [User!]
+              bio: String
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+
+        let src = "
+        type User = {
+            /**
+             * @gqlNonNull
+             * @gqlNullable
+             */
+            name: string;
+        }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Fields can't be tagged with both @gqlNonNull and @gqlNullable (check: name)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_deprecates_optional_args() {
+        let src = r#"
+        type Query = {
+            users: (args: {
+                /**
+                 * Name to search for
+                 * @deprecated Use `filter`
+                 */
+                name?: string | null;
+                /**
+                 * @default 10
+                 * @deprecated
+                 */
+                first?: number;
+                filter?: string;
+            }) => Promise<string[]>;
+            search(term: string, /** @deprecated */ limit?: number): Promise<string[]>;
+        }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            type Query {
+              users("Name to search for" name: String @deprecated(reason: "Use `filter`"), first: Int! = 10 @deprecated(reason: "No longer supported"), filter: String): [String!]!
+              search(term: String!, limit: Int @deprecated(reason: "No longer supported")): [String!]!
+            }
+            "# },
+            vec![("Query", GraphQLKind::Object)],
+        );
+
+        let src = "
+        type Query = {
+            users: (args: {
+                /** @deprecated */
+                first: number;
+            }) => Promise<string[]>;
+        }
+        ";
+        let err = test_expect_err(src, vec![("Query", GraphQLKind::Object)]);
+        assert_eq!(
+            "Arg `first` of field `users` is required and can't be deprecated, make it optional or give it a @default",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_keeps_optional_args_with_defaults_non_null() {
+        let src = r#"
+        type Query = {
+            users: (args: {
+                /** @default 10 */
+                first?: number;
+                /** @default 0 */
+                offset?: number | null;
+                after?: string;
+            }) => Promise<string[]>;
+            search(term: string, /** @default "NAME" */ sort?: string): Promise<string[]>;
+        }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            type Query {
+              users(first: Int! = 10, offset: Int = 0, after: String): [String!]!
+              search(term: String!, sort: String! = "NAME"): [String!]!
+            }
+            "# },
+            vec![("Query", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_unwraps_nested_promises() {
+        let src = "
+        type User = {
+            id: string;
+        }
+        type Query = {
+            user: (args: { id: string }) => Promise<Promise<User>>;
+            maybeUser: (args: { id: string }) => Promise<Promise<Promise<User | null>>>;
+            users(): Promise<Promise<User[]>>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Query {
+              user(id: String!): User!
+              maybeUser(id: String!): User
+              users: [User!]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_generates_enums_from_keyof() {
+        let src = "
+        type Query = {
+            users: (args: { sortBy: keyof User; fields?: (keyof User)[] }) => Promise<User[]>;
+        }
+        type User = {
+            /** Unique id */
+            id: string;
+            name: string;
+            posts(first: number): Promise<string[]>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            enum UserKeys {
+              id
+              name
+              posts
+            }
+            type Query {
+              users(sortBy: UserKeys!, fields: [UserKeys!]): [User!]!
+            }
+            type User {
+              "Unique id"
+              id: String!
+              name: String!
+              posts(first: Int!): [String!]!
+            }
+            "# },
+            vec![
+                ("Query", GraphQLKind::Object),
+                ("User", GraphQLKind::Object),
+            ],
+        );
+
+        test_with(
+            src,
+            indoc! { r#"
+            type Query {
+              users(sortBy: String!, fields: [String!]): [User!]!
+            }
+            type User {
+              "Unique id"
+              id: String!
+              name: String!
+              posts(first: Int!): [String!]!
+            }
+            "# },
+            vec![
+                ("Query", GraphQLKind::Object),
+                ("User", GraphQLKind::Object),
+            ],
+            |gen| gen.options.keyof_as_enums = false,
+        );
+    }
+
+    #[test]
+    fn it_errors_on_duplicate_fields() {
+        let src = "
+        type User = {
+            id: string;
+            name: string;
+            id: number;
+        }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!("Duplicate field in type User: id", err.to_string());
+
+        let src = "
+        type Query = {
+            user: (args: { id: string }) => Promise<string>;
+            user(id: string): Promise<string>;
+        }
+        ";
+        let err = test_expect_err(src, vec![("Query", GraphQLKind::Object)]);
+        assert_eq!("Duplicate field in type Query: user", err.to_string());
+
+        // Names are compared once sanitized
+        let sanitized = |src: &str| {
+            let manifest = vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ];
+            test_expect_err_with(src, manifest, |gen| gen.options.sanitize_names = true).to_string()
+        };
+        assert_eq!(
+            "Duplicate field in type User: a_b",
+            sanitized(r#"type User = { "a-b": string; a_b: string; }"#)
+        );
+        assert_eq!(
+            "Duplicate arg of field users: a_b",
+            sanitized(r#"type Query = { users(args: { "a-b": string; a_b: string }): string[] }"#)
+        );
+        assert_eq!(
+            "Duplicate value in enum UserRoleEnum: a_b",
+            sanitized(r#"type User = { role: "a-b" | "a_b"; }"#)
+        );
+    }
+
+    #[test]
+    fn it_reuses_unchanged_declarations() {
+        let manifest = || {
+            let mut map = HashMap::new();
+            map.insert("User".to_string(), GraphQLKind::Object);
+            map.insert("Post".to_string(), GraphQLKind::Object);
+            map.insert("Query".to_string(), GraphQLKind::Object);
+            map
+        };
+        let before = "
+        type User = {
+            id: string;
+            role: 'admin' | 'user';
+        }
+        type Post = {
+            title: string;
+        }
+        type Query = {
+            posts: (args: { author: string }) => Promise<Post[]>;
+        }
+        ";
+        // `Post` gains a field and `User` moves, which shouldn't invalidate it
+        let after = "
+        type Post = {
+            title: string;
+            author: User;
+        }
+
+
+        type User = {
+            id: string;
+            role: 'admin' | 'user';
+        }
+        type Query = {
+            posts: (args: { author: string }) => Promise<Post[]>;
+        }
+        ";
+
+        let mut cache = SchemaCache::new(CodeGenOptions::default());
+        let sdl = cache
+            .update(get_prog(before).module().unwrap(), manifest())
+            .unwrap();
+        assert_eq!(
+            generate_schema(get_prog(before).module().unwrap(), manifest()).unwrap(),
+            sdl
+        );
+        assert_eq!(["User", "Post", "Query"], cache.rebuilt());
+
+        let sdl = cache
+            .update(get_prog(after).module().unwrap(), manifest())
+            .unwrap();
+        assert_eq!(
+            generate_schema(get_prog(after).module().unwrap(), manifest()).unwrap(),
+            sdl
+        );
+        assert_eq!(["Post"], cache.rebuilt());
+
+        cache
+            .update(get_prog(after).module().unwrap(), manifest())
+            .unwrap();
+        assert!(cache.rebuilt().is_empty());
+
+        // A different manifest invalidates every declaration
+        let mut changed = manifest();
+        changed.remove("Post");
+        changed.insert("Post".to_string(), GraphQLKind::Interface);
+        cache
+            .update(get_prog(after).module().unwrap(), changed)
+            .unwrap();
+        assert_eq!(["Post", "User", "Query"], cache.rebuilt());
+
+        // So do the settings of its entries
+        let mut rooted = manifest()
+            .into_iter()
+            .map(|(name, kind)| (name, ManifestEntry::from(kind)))
+            .collect::<HashMap<_, _>>();
+        rooted.get_mut("Query").unwrap().root = Some(RootOperation::Query);
+        let sdl = cache
+            .update(get_prog(after).module().unwrap(), rooted)
+            .unwrap();
+        assert!(sdl.starts_with("schema {\n  query: Query\n}\n"));
+        assert_eq!(["Post", "User", "Query"], cache.rebuilt());
+
+        // Literal unions are inlined, so editing them rebuilds their users
+        let manifest = || {
+            let mut manifest = HashMap::new();
+            manifest.insert("User".to_string(), GraphQLKind::Object);
+            manifest
+        };
+        let before = "
+        type Role = 'a' | 'b';
+        type User = { role: Exclude<Role, 'a'>; }
+        ";
+        let after = before.replace("'a' | 'b'", "'a' | 'b' | 'c'");
+        let mut cache = SchemaCache::new(CodeGenOptions::default());
+        cache
+            .update(get_prog(before).module().unwrap(), manifest())
+            .unwrap();
+        let sdl = cache
+            .update(get_prog(&after).module().unwrap(), manifest())
+            .unwrap();
+        assert_eq!(
+            generate_schema(get_prog(&after).module().unwrap(), manifest()).unwrap(),
+            sdl
+        );
+        assert!(cache.rebuilt().contains(&"User".to_string()));
+
+        // So is the nullability of unions
+        let manifest = || {
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Post", GraphQLKind::Object),
+                ("Other", GraphQLKind::Object),
+                ("MaybePost", GraphQLKind::Union),
+            ]
+            .into_iter()
+            .map(|(name, kind)| (name.to_string(), kind))
+            .collect::<HashMap<_, _>>()
+        };
+        let before = "
+        type Post = { id: string; }
+        type Other = { id: string; }
+        type MaybePost = Post | null;
+        type User = { post: MaybePost; }
+        ";
+        let after = before.replace("Post | null", "Post | Other");
+        cache
+            .update(get_prog(before).module().unwrap(), manifest())
+            .unwrap();
+        let sdl = cache
+            .update(get_prog(&after).module().unwrap(), manifest())
+            .unwrap();
+        assert_eq!(
+            generate_schema(get_prog(&after).module().unwrap(), manifest()).unwrap(),
+            sdl
+        );
+        assert!(cache.rebuilt().contains(&"User".to_string()));
+    }
+
+    #[test]
+    fn it_unwraps_parenthesized_types() {
+        let src = "
+        type User = {
+            id: string;
+        }
+        type Query = {
+            names: (string | null)[];
+            users: ((User | undefined)[] | null)[];
+            statuses: ('active' | 'banned' | null)[];
+            friend: (User);
+            search(terms: (string | null)[], limit?: (number)): Promise<(User | null)[]>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            enum QueryStatusesEnum {
+              active
+              banned
+            }
+            type Query {
+              names: [String]!
+              users: [[User]]!
+              statuses: [QueryStatusesEnum]!
+              friend: User!
+              search(terms: [String]!, limit: Int): [User]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+
+        // Members of unions may be parenthesized too
+        let src = "
+        type User = { id: string; }
+        type Post = { id: string; }
+        type SearchResult = (User) | (Post) | null;
+        type Status = ('active') | 'banned';
+        type Search = { result: SearchResult; status: Status; score: (1 | 2) | null; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Post {
+              id: String!
+            }
+            union SearchResult = User | Post
+            enum Status {
+              active
+              banned
+            }
+            type Search {
+              result: SearchResult
+              status: Status!
+              score: Int
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Post", GraphQLKind::Object),
+                ("SearchResult", GraphQLKind::Union),
+                ("Status", GraphQLKind::Enum),
+                ("Search", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_generates_from_source() {
+        let mut manifest = HashMap::new();
+        manifest.insert("User".to_string(), GraphQLKind::Object);
+
+        let out = generate_schema_from_source(
+            "
+            type User = {
+                /** @gqlDirective auth */
+                id: string;
+                age?: number;
+            }
+            ",
+            manifest,
+            &TsParseConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            indoc! { r#"
+            type User {
+              id: String! @auth
+              age: Int
+            }
+            "# },
+            out
+        );
+    }
+
+    #[test]
+    fn it_synthesizes_types_from_inline_object_fields() {
+        let src = "
+        type User = {
+            id: string;
+            address: {
+                city: string;
+                geo?: { lat: number; lng: number } | null;
+            };
+            previous: { city: string }[];
+        }
+        type UserInput = {
+            address?: { city: string };
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type UserAddressGeo {
+              lat: Int!
+              lng: Int!
+            }
+            type UserAddress {
+              city: String!
+              geo: UserAddressGeo
+            }
+            type UserPrevious {
+              city: String!
+            }
+            type User {
+              id: String!
+              address: UserAddress!
+              previous: [UserPrevious!]!
+            }
+            input UserInputAddress {
+              city: String!
+            }
+            input UserInput {
+              address: UserInputAddress
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("UserInput", GraphQLKind::Input),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_unwraps_unions_with_multiple_nullable_members() {
+        let src = "
+        type User = {
+            id: string;
+        }
+        type Post = {
+            author: User | null;
+            editor: User | undefined | null;
+            reviewer: null | User | undefined;
+            tags: (string | null | undefined)[] | null;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Post {
+              author: User
+              editor: User
+              reviewer: User
+              tags: [String]
+            }
+            "# },
+            vec![("User", GraphQLKind::Object), ("Post", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_emits_unions_of_objects() {
+        let src = "
+        type User = { id: string; }
+        type Post = { id: string; }
+        /** Anything a search can find */
+        type SearchResult = User | Post | null;
+        type Search = { result: SearchResult; results: SearchResult[]; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Post {
+              id: String!
+            }
+            "Anything a search can find"
+            union SearchResult = User | Post
+            type Search {
+              result: SearchResult
+              results: [SearchResult]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Post", GraphQLKind::Object),
+                ("SearchResult", GraphQLKind::Union),
+                ("Search", GraphQLKind::Object),
+            ],
+        );
+
+        let src = "
+        type User = { id: string; }
+        type SearchResult = User | string;
+        ";
+        let err = test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("SearchResult", GraphQLKind::Union),
+            ],
+        );
+        assert_eq!(
+            "Members of union SearchResult must be references to object types",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_errors_on_unions_of_multiple_non_null_types() {
+        let src = "
+        type User = {
+            id: string;
+        }
+        type Post = {
+            id: string;
+        }
+        type Search = {
+            result: User | Post | null;
+        }
+        ";
+        let err = test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Post", GraphQLKind::Object),
+                ("Search", GraphQLKind::Object),
+            ],
+        );
+        assert_eq!(
+            "Unions of multiple non-null types are not supported, found 2",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_uses_custom_synthesized_suffixes() {
+        let src = "
+        type Query = {
+            findUser: (args: { where: { id: string } }) => Promise<{ name: string } | null>;
+            findUsers: (filter: { name: string }, page: { first: number }) => Promise<string[]>;
+        }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            input FindUserArgs {
+              id: String!
+            }
+            type FindUserPayload {
+              name: String!
+            }
+            input FindUsersArgsFilter {
+              name: String!
+            }
+            input FindUsersArgsPage {
+              first: Int!
+            }
+            type Query {
+              findUser(where: FindUserArgs!): FindUserPayload
+              findUsers(filter: FindUsersArgsFilter!, page: FindUsersArgsPage!): [String!]!
+            }
+            "# },
+            vec![("Query", GraphQLKind::Object)],
+            |gen| {
+                gen.options.input_suffix = "Args".into();
+                gen.options.output_suffix = "Payload".into();
+            },
+        );
+    }
+
+    #[test]
+    fn it_synthesizes_outputs_from_arrays_of_inline_objects() {
+        let src = "
+        type User = {
+            id: string;
+        }
+        type Query = {
+            users: (args: { first: number }) => Promise<{ user: User; cursor: string }[]>;
+            maybeUsers: (args: { ids: string[] }) => Promise<({ user: User } | null)[]>;
+            nested: (args: { first: number }) => Promise<{ id: string }[][] | null>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type UsersOutput {
+              user: User!
+              cursor: String!
+            }
+            type MaybeUsersOutput {
+              user: User!
+            }
+            type NestedOutput {
+              id: String!
+            }
+            type Query {
+              users(first: Int!): [UsersOutput!]!
+              maybeUsers(ids: [String!]!): [MaybeUsersOutput]!
+              nested(first: Int!): [[NestedOutput!]!]
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_names_nested_inline_outputs_by_path() {
+        let src = "
+        type Query = {
+            findUser: (args: { id: string }) => Promise<{
+                profile: { name: string };
+                stats: { posts: number; profile: { views: number } };
+            }>;
+            findPost: (args: { id: string }) => Promise<{ profile: { title: string } }>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type FindUserOutputProfile {
+              name: String!
+            }
+            type FindUserOutputStatsProfile {
+              views: Int!
+            }
+            type FindUserOutputStats {
+              posts: Int!
+              profile: FindUserOutputStatsProfile!
+            }
+            type FindUserOutput {
+              profile: FindUserOutputProfile!
+              stats: FindUserOutputStats!
+            }
+            type FindPostOutputProfile {
+              title: String!
+            }
+            type FindPostOutput {
+              profile: FindPostOutputProfile!
+            }
+            type Query {
+              findUser(id: String!): FindUserOutput!
+              findPost(id: String!): FindPostOutput!
+            }
+            "# },
+            vec![("Query", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_maps_generic_arrays_to_lists() {
+        let src = "
+        type User = {
+            tags: Array<string>;
+            aliases: Array<string | null>;
+            friends?: ReadonlyArray<Array<User>>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              tags: [String!]!
+              aliases: [String]!
+              friends: [[User!]!]
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_maps_sets_to_lists() {
+        let src = "
+        type User = {
+            tags: Set<string>;
+            scores?: ReadonlySet<number | null>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              tags: [String!]!
+              scores: [Int]
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+
+        let src = "
+        type User = { tags: Set<string, number>; }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Invalid amount of type parameters for Set: 2",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_maps_maps_to_a_scalar() {
+        let src = "
+        type User = {
+            scores: Map<string, number>;
+            friends?: ReadonlyMap<string, User>;
+        }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            scalar JSON
+            type User {
+              scores: JSON!
+              friends: JSON
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+            |gen| gen.options.map_representation = Some(MapRepresentation::Scalar("JSON".into())),
+        );
+
+        let src = "
+        type User = { scores: Map<string>; }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Invalid amount of type parameters for Map: 1",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_maps_maps_to_key_value_lists() {
+        let src = "
+        type User = {
+            id: string;
+            scores: Map<string, number | null>;
+        }
+        type Query = {
+            users: (args: { tags?: Map<string, string> }) => Promise<User[]>;
+            scores(tags: Map<string, number>): Promise<number>;
+        }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            type UserScoresKeyValue {
+              key: String!
+              value: Int
+            }
+            type User {
+              id: String!
+              scores: [UserScoresKeyValue!]!
+            }
+            input UsersInputTagsKeyValue {
+              key: String!
+              value: String!
+            }
+            input ScoresInputTagsKeyValue {
+              key: String!
+              value: Int!
+            }
+            type Query {
+              users(tags: [UsersInputTagsKeyValue!]): [User!]!
+              scores(tags: [ScoresInputTagsKeyValue!]!): Int!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+            |gen| gen.options.map_representation = Some(MapRepresentation::KeyValueList),
+        );
+    }
+
+    #[test]
+    fn it_parses_kinds_from_names() {
+        assert_eq!(Some(GraphQLKind::Object), GraphQLKind::from_name("object"));
+        assert_eq!(Some(GraphQLKind::Input), GraphQLKind::from_name("Input"));
+        assert_eq!(Some(GraphQLKind::Enum), GraphQLKind::from_name("ENUM"));
+        assert_eq!(
+            Some(GraphQLKind::Interface),
+            GraphQLKind::from_name("interface")
+        );
+        assert_eq!(Some(GraphQLKind::Union), GraphQLKind::from_name("Union"));
+        assert_eq!(None, GraphQLKind::from_name("scalar"));
+        assert_eq!(None, GraphQLKind::from_name(""));
+
+        assert_eq!(GraphQLKind::Input, "input".parse::<GraphQLKind>().unwrap());
+        let err = "scalar".parse::<GraphQLKind>().unwrap_err();
+        assert_eq!(
+            "Invalid GraphQL kind, expected object, input, enum, interface or union: scalar",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_declares_each_scalar_once() {
+        let src = "
+        type Event = {
+            payload: any;
+            meta?: unknown;
+            counts: Map<string, number>;
+            extra: { [key: string]: string };
+            size: bigint;
+            total: bigint;
+        }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            scalar JSON
+            scalar BigInt
+            type Event {
+              payload: JSON!
+              meta: JSON
+              counts: JSON!
+              extra: JSON!
+              size: BigInt!
+              total: BigInt!
+            }
+            "# },
+            vec![("Event", GraphQLKind::Object)],
+            |gen| {
+                gen.options.any_scalar = Some("JSON".into());
+                gen.options.index_signature_scalar = Some("JSON".into());
+                gen.options.map_representation = Some(MapRepresentation::Scalar("JSON".into()));
+            },
+        );
+    }
+
+    #[test]
+    fn it_resolves_scalar_aliases() {
+        let src = "
+        type Int = number;
+        type Float = number;
+        type ID = string;
+        type Cursor = string;
+        type Page = {
+            id: ID;
+            size: Int;
+            score?: Float;
+            next: Cursor;
+            items(first: Int, after?: Cursor): Promise<ID[]>;
+        }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            type Page {
+              id: ID!
+              size: Int!
+              score: Float
+              next: ID!
+              items(first: Int!, after: ID): [ID!]!
+            }
+            "# },
+            vec![("Page", GraphQLKind::Object)],
+            |gen| {
+                gen.options
+                    .scalar_aliases
+                    .insert("Cursor".into(), "ID".into());
+            },
+        );
+    }
+
+    #[test]
+    fn it_validates_generated_schemas() {
+        let src = "
+        type BigInt = {
+            value: string;
+        }
+        type Account = {
+            balance: bigint;
+        }
+        ";
+        let manifest = || {
+            let mut map = HashMap::new();
+            map.insert("BigInt".to_string(), GraphQLKind::Object);
+            map.insert("Account".to_string(), GraphQLKind::Object);
+            map
+        };
+
+        // the user's `BigInt` type collides with the scalar synthesized for `bigint`
+        let sdl = generate_schema_with_validation(
+            get_prog(src).module().unwrap(),
+            manifest(),
+            CodeGenOptions::default(),
+            false,
+        )
+        .unwrap();
+        assert!(sdl.contains("scalar BigInt") && sdl.contains("type BigInt"));
+
+        let err = generate_schema_with_validation(
+            get_prog(src).module().unwrap(),
+            manifest(),
+            CodeGenOptions::default(),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Generated schema is invalid: type `BigInt` is defined more than once",
+            err.to_string()
+        );
+
+        assert!(validate_schema("type User {\n  id: String!\n}\n").is_ok());
+        assert!(validate_schema("type User {\n  id: String!\n").is_err());
+    }
+
+    #[test]
+    fn it_parses_descriptions_of_fields_inputs_and_args() {
+        let src = "
+        /** Filters for users */
+        type UserFilter = {
+            /** Only active users */
+            active?: boolean;
+        }
+        type User = {
+            /** Unique id */
+            id: string;
+        }
+        type Query = {
+            /** Finds users */
+            users: (args: {
+                /** Filters to apply */
+                filter: UserFilter;
+                /**
+                 * Max amount of users,
+                 * defaults to all
+                 */
+                limit?: number;
+            }) => Promise<User[]>;
+            user(/** Id of the user */ id: string, verbose?: boolean): Promise<User | null>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            input UserFilter {
+              "Only active users"
+              active: Boolean
+            }
+            type User {
+              "Unique id"
+              id: String!
+            }
+            type Query {
+              "Finds users"
+              users("Filters to apply" filter: UserFilter!, """
+            Max amount of users,
+            defaults to all
+            """ limit: Int): [User!]!
+              user("Id of the user" id: String!, verbose: Boolean): User
+            }
+            "# },
+            vec![
+                ("UserFilter", GraphQLKind::Input),
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_describes_synthesized_types() {
+        let src = "
+        type Query = {
+            /** Users matching the filter */
+            users(
+                /** Filters to apply */
+                filter: { active: boolean },
+                limit?: number
+            ): Promise<{ total: number }>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            "Filters to apply"
+            input UsersInputFilter {
+              active: Boolean!
+            }
+            "Users matching the filter"
+            type UsersOutput {
+              total: Int!
+            }
+            type Query {
+              "Users matching the filter"
+              users("Filters to apply" filter: UsersInputFilter!, limit: Int): UsersOutput!
+            }
+            "# },
+            vec![("Query", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_resolves_qualified_type_references() {
+        let src = "
+        declare namespace Schema {
+            type User = { id: string; }
+        }
+        type Query = { me: () => Promise<Schema.User>; admins: Schema.User[]; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type Query {
+              me: User!
+              admins: [User!]!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+
+        let src = "
+        type Query = { me: () => Promise<Schema.Models.User>; }
+        type Schema_Models_User = { id: string; }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            type Query {
+              me: Schema_Models_User!
+            }
+            type Schema_Models_User {
+              id: String!
+            }
+            "# },
+            vec![
+                ("Schema_Models_User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+            |ctx| ctx.options.qualified_name_separator = Some("_".into()),
+        );
+    }
+
+    #[test]
+    fn it_collapses_template_literals_to_strings() {
+        let src = "
+        type User = { id: `user_${string}`; slug?: `${string}-${number}`; }
+        ";
+        let prog = get_prog(src);
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+
+        let (out, diagnostics) = generate_schema_with_diagnostics(
+            prog.module().unwrap(),
+            map,
+            CodeGenOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            indoc! { r#"
+            type User {
+              id: String!
+              slug: String
+            }
+            "# },
+            out
+        );
+        assert_eq!(
+            vec![
+                Diagnostic::TemplateLiteral("id".into()),
+                Diagnostic::TemplateLiteral("slug".into()),
+            ],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn it_applies_nullish_policies() {
+        let src = "
+        type User = {
+            id: string;
+            name: string | null;
+            nickname: string | undefined;
+            bio?: string;
+            friends(args: { first: number | undefined }): Promise<User | null>;
+        }
+        ";
+        let mani = || vec![("User", GraphQLKind::Object)];
+
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String
+              nickname: String
+              bio: String
+              friends(first: Int): User
+            }
+            "# },
+            mani(),
+        );
+        test_with(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String
+              nickname: String!
+              bio: String
+              friends(first: Int!): User
+            }
+            "# },
+            mani(),
+            |ctx| ctx.options.undefined_policy = NullishPolicy::Ignore,
+        );
+        test_with(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+              nickname: String
+              bio: String
+              friends(first: Int): User!
+            }
+            "# },
+            mani(),
+            |ctx| ctx.options.null_policy = NullishPolicy::Ignore,
+        );
+
+        let err = test_expect_err_with(src, vec![("User", GraphQLKind::Object)], |gen| {
+            gen.options.undefined_policy = NullishPolicy::Reject
+        });
+        assert_eq!(
+            "`undefined` is not allowed in types by the configured nullability policy",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_parses_enum_descriptions_and_deprecations() {
+        let src = "
+        /** Access level of a user */
+        export type Role =
+            /** Can do anything */
+            'ADMIN'
+            | /**
+               * Superseded by admins
+               * @deprecated Use ADMIN
+               */
+              'ROOT'
+            | 'USER';
+
+        /** Status of a post */
+        enum Status {
+            /** Not visible yet */
+            DRAFT = 'DRAFT',
+            /** @deprecated */
+            HIDDEN = 'HIDDEN',
+            'PUBLISHED',
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            "Access level of a user"
+            enum Role {
+              "Can do anything"
+              ADMIN
+              "Superseded by admins"
+              ROOT @deprecated(reason: "Use ADMIN")
+              USER
+            }
+            "Status of a post"
+            enum Status {
+              "Not visible yet"
+              DRAFT
+              HIDDEN @deprecated(reason: "No longer supported")
+              PUBLISHED
+            }
+            "# },
+            vec![("Role", GraphQLKind::Enum), ("Status", GraphQLKind::Enum)],
+        );
+    }
+
+    #[test]
+    fn it_parses_exported_types_like_local_ones() {
+        let local = "
+        type User = { id: string; role: Role; }
+        type Role = 'ADMIN' | 'USER';
+        ";
+        let exported = "
+        export type User = { id: string; role: Role; }
+        export type Role = 'ADMIN' | 'USER';
+        ";
+        let expected = indoc! { r#"
+            type User {
+              id: String!
+              role: Role!
+            }
+            enum Role {
+              ADMIN
+              USER
+            }
+            "# };
+        let mani = || vec![("User", GraphQLKind::Object), ("Role", GraphQLKind::Enum)];
+        test(local, expected, mani());
+        test(exported, expected, mani());
+    }
+
+    #[test]
+    fn it_parses_interface_declarations() {
+        let src = "
+        export interface User { id: string; posts(args: { first: number }): Promise<Post[]>; }
+        interface Post { title: string; }
+        export interface CreatePostInput { title: string; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              posts(first: Int!): [Post!]!
+            }
+            type Post {
+              title: String!
+            }
+            input CreatePostInput {
+              title: String!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Post", GraphQLKind::Object),
+                ("CreatePostInput", GraphQLKind::Input),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_fails_on_extended_interfaces() {
+        let src = "
+        interface Node { id: string; }
+        interface User extends Node { name: string; }
+        ";
+        let err = test_expect_err(
+            src,
+            vec![("Node", GraphQLKind::Object), ("User", GraphQLKind::Object)],
+        );
+        assert_eq!(
+            "Interfaces that extend other types are not supported, use a type alias instead (check: User)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_parses_declaration_files() {
+        let src = "
+        export declare type User = { id: string; name: string; };
+        declare function getUser(id: string): User;
+        declare global {
+            type Session = { user: User; expires: number; }
+        }
+        declare module 'app' {
+            export type Role = 'ADMIN' | 'USER';
+        }
+        declare namespace Api.V1 {
+            type Query = { me: () => Promise<User>; }
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+            }
+            type Session {
+              user: User!
+              expires: Int!
+            }
+            enum Role {
+              ADMIN
+              USER
+            }
+            type Query {
+              me: User!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Session", GraphQLKind::Object),
+                ("Role", GraphQLKind::Enum),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+    }
+
+    #[test]
+    fn it_generates_from_multiple_modules() {
+        let user = get_prog("type User = { id: string; name: string; }");
+        let post = get_prog(
+            "
+            type Post = { title: string; author: User; }
+            type User = { id: string; name: string; }
+            ",
+        );
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+        map.insert("Post".into(), GraphQLKind::Object);
+
+        let out = generate_schema_multi(
+            vec![user.module().unwrap(), post.module().unwrap()],
+            map,
+            CodeGenOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+            }
+            type Post {
+              title: String!
+              author: User!
+            }
+            "# },
+            out
+        );
+    }
+
+    #[test]
+    fn it_resolves_imported_types() {
+        let user = get_prog("type User = { id: string; name: string; }");
+        let resolvers = get_prog(
+            "
+            import { User } from './user';
+            import type { User as Account } from './user';
+            type Query = { me: () => Promise<User>; account: () => Promise<Account | null>; }
+            ",
+        );
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+        map.insert("Query".into(), GraphQLKind::Object);
+
+        let mut gen = CodeGenCtx::new(map, CodeGenOptions::default());
+        gen.parse(resolvers.module().unwrap()).unwrap();
+        assert_eq!(
+            Some(&("User".to_string(), "./user".to_string())),
+            gen.imports.get("Account")
+        );
+        gen.parse(user.module().unwrap()).unwrap();
+        assert!(gen.imports.is_empty());
+        assert_eq!(
+            indoc! { r#"
+            type Query {
+              me: User!
+              account: User
+            }
+            type User {
+              id: String!
+              name: String!
+            }
+            "# },
+            gen.finish().unwrap()
+        );
+    }
+
+    #[test]
+    fn it_fails_on_colliding_definitions_across_modules() {
+        let a = get_prog("type User = { id: string; }");
+        let b = get_prog("type User = { name: string; }");
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+
+        let err = generate_schema_multi(
+            vec![a.module().unwrap(), b.module().unwrap()],
+            map,
+            CodeGenOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Type is defined more than once with different fields: User",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_fails_on_undeclared_references() {
+        let src = "
+        type User = { id: string; address: Address; company?: Company; }
+        ";
+        let prog = get_prog(src);
+        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
+        map.insert("User".into(), GraphQLKind::Object);
+        map.insert("Address".into(), GraphQLKind::Object);
+        map.insert("Company".into(), GraphQLKind::Object);
+
+        let mut gen = CodeGenCtx::new(map, CodeGenOptions::default());
+        gen.parse(prog.module().unwrap()).unwrap();
+        let err = gen.finish().unwrap_err();
+        assert_eq!(
+            "Types referenced but never declared: Address, Company",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_parses_recursive_types() {
+        // References are only looked up in the manifest, so self-references don't recurse
+        let src = "
+        type Comment = { id: string; replies: Comment[]; parent?: Comment; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type Comment {
+              id: String!
+              replies: [Comment!]!
+              parent: Comment
+            }
+            "# },
+            vec![("Comment", GraphQLKind::Object)],
+        );
+
+        // Mutual recursion
+        let src = "
+        type User = { id: string; posts: Post[]; }
+        type Post = { id: string; author: User; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+              posts: [Post!]!
+            }
+            type Post {
+              id: String!
+              author: User!
+            }
+            "# },
+            vec![("User", GraphQLKind::Object), ("Post", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_parses_string_literal_unions_as_enums() {
+        let src = r#"
+        type Issue = { id: string; state: "open" | "closed" | "pending"; }
+        type Review = { verdict?: "approve" | "reject"; previous: "approve" | "reject" | null; }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            enum IssueStateEnum {
+              open
+              closed
+              pending
+            }
+            type Issue {
+              id: String!
+              state: IssueStateEnum!
+            }
+            enum ReviewVerdictEnum {
+              approve
+              reject
+            }
+            enum ReviewPreviousEnum {
+              approve
+              reject
+            }
+            type Review {
+              verdict: ReviewVerdictEnum
+              previous: ReviewPreviousEnum
+            }
+            "# },
+            vec![
+                ("Issue", GraphQLKind::Object),
+                ("Review", GraphQLKind::Object),
+            ],
+        );
+
+        test_with(
+            src,
+            indoc! { r#"
+            type Issue {
+              id: String!
+              state: String!
+            }
+            type Review {
+              verdict: String
+              previous: String
+            }
+            "# },
+            vec![
+                ("Issue", GraphQLKind::Object),
+                ("Review", GraphQLKind::Object),
+            ],
+            |gen| gen.options.literal_unions_as_enums = false,
+        );
+    }
+
+    #[test]
+    fn it_names_literal_union_enums_after_their_type() {
+        let src = r#"
+        type User = { status: "active" | "banned"; level: 1 | 2; }
+        type Post = { status: "draft" | "published"; score?: 0.5 | 1; }
+        "#;
+        let manifest = || vec![("User", GraphQLKind::Object), ("Post", GraphQLKind::Object)];
+        test(
+            src,
+            indoc! { r#"
+            enum UserStatusEnum {
+              active
+              banned
+            }
+            type User {
+              status: UserStatusEnum!
+              level: Int!
+            }
+            enum PostStatusEnum {
+              draft
+              published
+            }
+            type Post {
+              status: PostStatusEnum!
+              score: Float
+            }
+            "# },
+            manifest(),
+        );
+
+        let src = r#"type User = { visible: "true" | "false" | "hidden"; }"#;
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "`true` can't be the value of an enum (check: UserVisibleEnum)",
+            err.to_string()
+        );
+        test_with(
+            src,
+            indoc! { r#"
+            enum UserVisibleEnum {
+              _true
+              _false
+              hidden
+            }
+            type User {
+              visible: UserVisibleEnum!
+            }
+            "# },
+            vec![("User", GraphQLKind::Object)],
+            |gen| gen.options.sanitize_names = true,
+        );
+    }
+
+    #[test]
+    fn it_filters_literal_unions_with_exclude_and_extract() {
+        let src = r#"
+        type Role = "admin" | "editor" | "viewer";
+        type Invite = { role: Exclude<Role, "admin">; fallback?: Extract<Role, "editor" | "viewer">; }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            enum Role {
+              admin
+              editor
+              viewer
+            }
+            enum InviteRoleEnum {
+              editor
+              viewer
+            }
+            enum InviteFallbackEnum {
+              editor
+              viewer
+            }
+            type Invite {
+              role: InviteRoleEnum!
+              fallback: InviteFallbackEnum
+            }
+            "# },
+            vec![("Role", GraphQLKind::Enum), ("Invite", GraphQLKind::Object)],
+        );
+
+        let src = r#"
+        type User = { id: string };
+        type Invite = { user: Exclude<User, "admin">; }
+        "#;
+        let err = test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Invite", GraphQLKind::Object),
+            ],
+        );
+        assert_eq!(
+            "Exclude is only supported over unions of string literals (check: user)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_prefixes_type_names() {
+        let src = r#"
+        type Node = { id: string; }
+        type User = { id: string; role: "admin" | "member"; friends: User[]; }
+        type Query = { user(args: { id: string }): User | null; ids: string[]; }
+        "#;
+        test_with(
+            src,
+            indoc! { r#"
+            interface Foo_Node {
+              id: String!
+            }
+            enum Foo_UserRoleEnum {
+              admin
+              member
+            }
+            type Foo_User {
+              id: String!
+              role: Foo_UserRoleEnum!
+              friends: [Foo_User!]!
+            }
+            type Query {
+              user(id: String!): Foo_User
+              ids: [String!]!
+            }
+            "# },
+            vec![
+                ("Node", GraphQLKind::Interface),
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+            |gen| gen.options.type_prefix = Some("Foo_".into()),
+        );
+    }
+
+    #[test]
+    fn it_writes_the_prologue_first() {
+        let src = "
+        type User = { id: string; }
+        type RootQuery = { me: User; }
+        ";
+        let link = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@key"])"#;
+        let mut map = HashMap::new();
+        map.insert("User".to_string(), ManifestEntry::from(GraphQLKind::Object));
+        map.insert(
+            "RootQuery".to_string(),
+            ManifestEntry {
+                root: Some(RootOperation::Query),
+                ..GraphQLKind::Object.into()
+            },
+        );
+        let options = CodeGenOptions {
+            prologue: vec![link.to_string()],
+            ..Default::default()
+        };
+        let mut gen = CodeGenCtx::with_manifest(map, options);
+        gen.parse(get_prog(src).module().unwrap()).unwrap();
+        assert_eq!(
+            format!(
+                "{}\n{}",
+                link,
+                indoc! { r#"
+                schema {
+                  query: RootQuery
+                }
+                type User {
+                  id: String!
+                }
+                type RootQuery {
+                  me: User!
+                }
+                "# }
+            ),
+            gen.finish().unwrap()
+        );
+    }
+
+    #[test]
+    fn it_emits_key_directives() {
+        let src = "
+        /** @gqlKey id */
+        type User = { id: string; }
+        /**
+         * @gqlKey sku
+         * @gqlKey upc variation { id }
+         */
+        interface Product { sku: string; upc: string; variation: Variation; }
+        type Variation = { id: string; }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User @key(fields: "id") {
+              id: String!
+            }
+            type Product @key(fields: "sku") @key(fields: "upc variation { id }") {
+              sku: String!
+              upc: String!
+              variation: Variation!
+            }
+            type Variation {
+              id: String!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Product", GraphQLKind::Object),
+                ("Variation", GraphQLKind::Object),
+            ],
+        );
+
+        // Keys follow the implemented interfaces and the directives of the manifest entry
+        let src = "
+        /** @gqlKey id */
+        interface Node { id: string; }
+        /** @gqlKey id */
+        type User = { id: string; }
+        ";
+        let (prog, comments) = get_prog_with_comments(src);
+        let mut manifest: HashMap<String, ManifestEntry> = HashMap::new();
+        manifest.insert("Node".into(), GraphQLKind::Interface.into());
+        manifest.insert(
+            "User".into(),
+            ManifestEntry {
+                extends: vec!["Node".into()],
+                directives: vec!["shareable".into()],
+                ..GraphQLKind::Object.into()
+            },
+        );
+        let out =
+            generate_schema_with_comments(prog.module().unwrap(), comments, manifest).unwrap();
+        assert_eq!(
+            indoc! { r#"
+            interface Node @key(fields: "id") {
+              id: String!
+            }
+            type User implements Node @shareable @key(fields: "id") {
+              id: String!
+            }
+            "# },
+            out
+        );
+
+        let src = "
+        /** @gqlKey */
+        type User = { id: string; }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Invalid @gqlKey, expected the fields of the key (check: User)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_instantiates_generic_aliases() {
+        let src = "
+        type User = { id: string; }
+        type PageInfo = { hasNextPage: boolean; endCursor: string | null; }
+        type Edge<T> = { cursor: string; node: T; }
+        type Connection<T> = { edges: Edge<T>[]; pageInfo: PageInfo; totalCount: number; }
+        type Query = {
+            users(first: number): Promise<Connection<User>>;
+            tags: Connection<string>;
+        }
+        ";
+        test(
+            src,
+            indoc! { r#"
+            type User {
+              id: String!
+            }
+            type PageInfo {
+              hasNextPage: Boolean!
+              endCursor: String
+            }
+            type UserEdge {
+              cursor: String!
+              node: User!
+            }
+            type UserConnection {
+              edges: [UserEdge!]!
+              pageInfo: PageInfo!
+              totalCount: Int!
+            }
+            type StringEdge {
+              cursor: String!
+              node: String!
+            }
+            type StringConnection {
+              edges: [StringEdge!]!
+              pageInfo: PageInfo!
+              totalCount: Int!
+            }
+            type Query {
+              users(first: Int!): UserConnection!
+              tags: StringConnection!
+            }
+            "# },
+            vec![
+                ("User", GraphQLKind::Object),
+                ("PageInfo", GraphQLKind::Object),
+                ("Edge", GraphQLKind::Object),
+                ("Connection", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+
+        let src = "
+        type Connection<T> = { edges: T[]; }
+        type Query = { users: Connection<string>; }
+        ";
+        let err = test_expect_err(src, vec![("Query", GraphQLKind::Object)]);
+        assert_eq!(
+            "Generic types must be manifested as objects or inputs (check: Connection)",
+            err.to_string()
+        );
+
+        let src = "
+        type User = { id: string; }
+        type UserConnection = { total: number; }
+        type Connection<T> = { edges: T[]; }
+        type Query = { users: Connection<User>; }
+        ";
+        let err = test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("UserConnection", GraphQLKind::Object),
+                ("Connection", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+        assert_eq!(
+            "Conflicting definitions generated for type: UserConnection",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_emits_enums_from_const_objects() {
+        let src = r#"
+        /** Access level of a user */
+        export const Role = {
+            Admin: "admin",
+            /**
+             * @deprecated Use Admin
+             */
+            Owner: "owner",
+            "Member": "member",
+        } as const;
+        export type Role = typeof Role[keyof typeof Role];
+        const Limits = { Max: 10 } as const;
+        type User = { name: string; role: Role; }
+        "#;
+        test(
+            src,
+            indoc! { r#"
+            "Access level of a user"
+            enum Role {
+              Admin
+              Owner @deprecated(reason: "Use Admin")
+              Member
+            }
+            type User {
+              name: String!
+              role: Role!
+            }
+            "# },
+            vec![("Role", GraphQLKind::Enum), ("User", GraphQLKind::Object)],
+        );
+
+        let src = "
+        const Role = { ...Base, Admin: 'admin' } as const;
+        type User = { role: Role; }
+        ";
+        let err = test_expect_err(
+            src,
+            vec![("Role", GraphQLKind::Enum), ("User", GraphQLKind::Object)],
+        );
+        assert_eq!(
+            "Const objects manifested as enums can only have properties named with identifiers or strings (check: Role)",
+            err.to_string()
+        );
+
+        // Keys become enum values, so they must be valid names
+        let src = r#"
+        const Role = { "super-admin": "super", Admin: "admin" } as const;
+        type User = { role: Role; }
+        "#;
+        let manifest = || vec![("Role", GraphQLKind::Enum), ("User", GraphQLKind::Object)];
+        let err = test_expect_err(src, manifest());
+        assert_eq!(
+            "`super-admin` is not a valid GraphQL name (check: Role)",
+            err.to_string()
+        );
+        test_with(
+            src,
+            indoc! { r#"
+            enum Role {
+              super_admin
+              Admin
+            }
+            type User {
+              role: Role!
+            }
+            "# },
+            manifest(),
+            |gen| gen.options.sanitize_names = true,
+        );
+    }
 
-        gen.parse(prog.module().unwrap()).unwrap();
-        let out = gen.finish();
-        println!("{}", out);
-        assert_eq!(expected, out);
+    #[test]
+    fn it_rejects_tuples_with_optional_elements() {
+        // Optional types only appear as tuple elements, which have no GraphQL representation
+        let err = test_expect_err(
+            "type User = { pair: [string, number?]; }",
+            vec![("User", GraphQLKind::Object)],
+        );
+        assert_eq!(
+            "Field `pair` has a tuple type, which has no GraphQL representation",
+            err.to_string()
+        );
     }
 
-    fn test_expect_err(src: &str, mani: Vec<(&str, GraphQLKind)>) {
-        let prog = get_prog(src);
-        let mut map: HashMap<String, GraphQLKind> = HashMap::new();
-        mani.into_iter().for_each(|(k, v)| {
-            map.insert(k.into(), v);
-        });
-        let mut gen = CodeGenCtx::new(map);
-        match gen.parse(prog.module().unwrap()) {
-            Err(_) => {}
-            Ok(_) => {
-                println!("Output: {}", gen.finish());
-                panic!("Expected error")
-            }
-        }
+    #[test]
+    fn it_computes_synthesized_names() {
+        assert_eq!("", upper_camel_case(""));
+        assert_eq!("FindUser", upper_camel_case("findUser"));
+        assert_eq!("Édition", upper_camel_case("Édition"));
+
+        let mut gen = CodeGenCtx::new(HashMap::new(), CodeGenOptions::default());
+        assert_eq!(
+            "FindUserOutput",
+            gen.compute_new_name(ComputeNameKind::Output, "findUser")
+        );
+        assert_eq!(
+            "StatusEnum",
+            gen.compute_new_name(ComputeNameKind::Enum, "status")
+        );
+        gen.parent_types.push(("User".into(), FieldKind::Object));
+        assert_eq!(
+            "UserStatusEnum",
+            gen.compute_new_name(ComputeNameKind::Enum, "status")
+        );
+        gen.parent_types.pop();
+        assert_eq!(
+            "FindUserInput",
+            gen.compute_new_name(ComputeNameKind::Input("args", 1), "findUser")
+        );
+        assert_eq!(
+            "FindUserInputFilter",
+            gen.compute_new_name(ComputeNameKind::Input("filter", 2), "findUser")
+        );
+        gen.options.always_suffix_input_names = true;
+        assert_eq!(
+            "FindUserInputArgs",
+            gen.compute_new_name(ComputeNameKind::Input("args", 1), "findUser")
+        );
     }
 
     #[test]
-    fn it_parses_field_basic_types() {
+    fn it_generates_with_options() {
         let src = "
-        type User = { id: string; name: string; karma: number; active: boolean; }
-        type Player = { user: User; level: number; }
+        type User = { id: number; }
+        type Query = { user(id: number): Promise<{ user: User }>; }
         ";
+        let manifest: HashMap<String, GraphQLKind> = vec![
+            ("User".to_string(), GraphQLKind::Object),
+            ("Query".to_string(), GraphQLKind::Object),
+        ]
+        .into_iter()
+        .collect();
+        let options: CodeGenOptions =
+            serde_json::from_str(r#"{ "forceNullable": true, "outputSuffix": "Payload" }"#)
+                .unwrap();
+        assert_eq!(
+            CodeGenOptions {
+                force_nullable: true,
+                output_suffix: "Payload".into(),
+                ..Default::default()
+            },
+            options
+        );
+
+        let (prog, comments) = get_prog_with_comments(src);
+        let out = generate_schema_with_options(prog.module().unwrap(), comments, manifest, options)
+            .unwrap();
+        assert_eq!(
+            indoc! { r#"
+            type User {
+              id: Int
+            }
+            type UserPayload {
+              user: User
+            }
+            type Query {
+              user(id: Int!): UserPayload
+            }
+            "# },
+            out
+        );
+    }
+
+    #[test]
+    fn it_derives_inputs_and_objects_with_pick_and_omit() {
+        let src = r#"
+        type User = { id: string; name: string; email: string; }
+        type UserKeys = "id" | "name";
+        type UserPreview = Pick<User, UserKeys>;
+        type UserPatch = Omit<User, "id">;
+        type Query = {
+            me: Pick<User, "id" | "name">;
+            updateUser(args: { user: Pick<User, "id" | "name">; patch: UserPatch }): UserPreview;
+        }
+        "#;
         test(
             src,
             indoc! { r#"
             type User {
               id: String!
               name: String!
-              karma: Int!
-              active: Boolean!
+              email: String!
             }
-            type Player {
-              user: User!
-              level: Int!
+            type UserPreview {
+              id: String!
+              name: String!
+            }
+            input UserPatch {
+              name: String!
+              email: String!
+            }
+            type QueryMe {
+              id: String!
+              name: String!
+            }
+            input UpdateUserInputUser {
+              id: String!
+              name: String!
+            }
+            type Query {
+              me: QueryMe!
+              updateUser(user: UpdateUserInputUser!, patch: UserPatch!): UserPreview
             }
             "# },
             vec![
                 ("User", GraphQLKind::Object),
-                ("Player", GraphQLKind::Object),
+                ("UserPreview", GraphQLKind::Object),
+                ("UserPatch", GraphQLKind::Input),
+                ("Query", GraphQLKind::Object),
+            ],
+        );
+
+        // Cached picks are rebuilt when the type they pick from changes
+        let manifest = || {
+            vec![
+                ("User", GraphQLKind::Object),
+                ("UserPreview", GraphQLKind::Object),
+            ]
+            .into_iter()
+            .map(|(name, kind)| (name.to_string(), kind))
+            .collect::<HashMap<_, _>>()
+        };
+        let before = r#"
+        type User = { id: string; name: string; }
+        type UserPreview = Pick<User, "name">;
+        "#;
+        let after = before.replace("name: string;", "name: string | null;");
+        let mut cache = SchemaCache::new(CodeGenOptions::default());
+        cache
+            .update(get_prog(before).module().unwrap(), manifest())
+            .unwrap();
+        let sdl = cache
+            .update(get_prog(&after).module().unwrap(), manifest())
+            .unwrap();
+        assert_eq!(["User", "UserPreview"], cache.rebuilt());
+        assert!(sdl.ends_with("type UserPreview {\n  name: String\n}\n"));
+
+        let src = r#"
+        type User = { id: string; }
+        type Query = { me: Pick<User, "age">; }
+        "#;
+        let err = test_expect_err(
+            src,
+            vec![
+                ("User", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
             ],
         );
+        assert_eq!(
+            "Pick of a type without the field `age` (check: me)",
+            err.to_string()
+        );
+    }
 
-        // Optionals
+    #[test]
+    fn it_mirrors_objects_referenced_as_inputs() {
         let src = "
-        type User = { id?: string; name?: string; karma?: number; }
-        type Player = { user: User; level?: number; }
+        type Address = { street: string; city?: string; }
+        type User = {
+            /** Display name */
+            name: string;
+            address: Address;
+            posts(first: number): Promise<string[]>;
+        }
+        type Mutation = { updateUser(user: User, previous?: User): Promise<User>; }
         ";
-
-        test(
+        test_with(
             src,
             indoc! { r#"
+            type Address {
+              street: String!
+              city: String
+            }
             type User {
-              id: String
-              name: String
-              karma: Int
+              "Display name"
+              name: String!
+              address: Address!
+              posts(first: Int!): [String!]!
             }
-            type Player {
-              user: User!
-              level: Int
+            input AddressInput {
+              street: String!
+              city: String
+            }
+            input UserInput {
+              "Display name"
+              name: String!
+              address: AddressInput!
+            }
+            type Mutation {
+              updateUser(user: UserInput!, previous: UserInput): User!
             }
             "# },
             vec![
+                ("Address", GraphQLKind::Object),
                 ("User", GraphQLKind::Object),
-                ("Player", GraphQLKind::Object),
+                ("Mutation", GraphQLKind::Object),
             ],
+            |gen| gen.options.mirror_inputs = true,
         );
-    }
 
-    #[test]
-    fn it_fails_when_a_field_is_an_input() {
-        // Basic
+        // Mirrors are named with `input_suffix`, like inline args
         let src = "
-                type User = { id: string; name: string; karma: number; }
-                type AnInput = { dummy: string }
-                type Player = { user: User[]; woops: AnInput; }
-                ";
-        test_expect_err(
+        type User = { name: string; }
+        type Mutation = { updateUser(user: User): Promise<User>; }
+        ";
+        test_with(
             src,
+            indoc! { r#"
+            type User {
+              name: String!
+            }
+            input UserArgs {
+              name: String!
+            }
+            type Mutation {
+              updateUser(user: UserArgs!): User!
+            }
+            "# },
             vec![
                 ("User", GraphQLKind::Object),
-                ("AnInput", GraphQLKind::Input),
-                ("Player", GraphQLKind::Object),
+                ("Mutation", GraphQLKind::Object),
             ],
+            |gen| {
+                gen.options.mirror_inputs = true;
+                gen.options.input_suffix = "Args".to_string();
+            },
         );
+    }
 
-        // Field with args
+    #[test]
+    fn it_reports_progress_for_each_type() {
+        let src = r#"
+        type Role = "admin" | "member";
+        type Skipped = { id: string; }
+        interface User { id: string; role: Role; address: { city: string }; }
+        type Query = { me: User; }
+        "#;
+        let (prog, comments) = get_prog_with_comments(src);
+        let manifest: HashMap<String, GraphQLKind> = vec![
+            ("Role".to_string(), GraphQLKind::Enum),
+            ("User".to_string(), GraphQLKind::Object),
+            ("Query".to_string(), GraphQLKind::Object),
+        ]
+        .into_iter()
+        .collect();
+        let mut types = Vec::new();
+        generate_schema_with_progress(
+            prog.module().unwrap(),
+            comments,
+            manifest,
+            Default::default(),
+            |name| types.push(name.to_string()),
+        )
+        .unwrap();
+        assert_eq!(vec!["Role", "User", "Query"], types);
+    }
+
+    #[test]
+    fn it_generates_with_a_shared_manifest() {
         let src = "
-                type AnInput = { id: string; name: string; karma: number; }
-                type Query = { doSomething: (input: { whatever: string }) => Promise<AnInput> }
-                ";
-        test_expect_err(
+        type User = { id: string; }
+        type Query = { me: User; }
+        ";
+        let mut entries: HashMap<String, ManifestEntry> = HashMap::new();
+        entries.insert("User".to_string(), GraphQLKind::Object.into());
+        entries.insert(
+            "Query".to_string(),
+            ManifestEntry {
+                root: Some(RootOperation::Query),
+                ..GraphQLKind::Object.into()
+            },
+        );
+        let manifest = Arc::new(entries);
+
+        // Every call reads the same entries
+        let outputs = (0..2)
+            .map(|_| {
+                let (prog, comments) = get_prog_with_comments(src);
+                generate_schema_with_shared_manifest(
+                    prog.module().unwrap(),
+                    comments,
+                    Arc::clone(&manifest),
+                    Default::default(),
+                    |_| {},
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(outputs[0], outputs[1]);
+        assert!(outputs[0].starts_with("schema {\n  query: Query\n}\n"));
+        assert_eq!(1, Arc::strong_count(&manifest));
+    }
+
+    #[test]
+    fn it_fails_on_manifested_enums_that_arent_unions() {
+        let src = "
+        type Role = { id: string };
+        ";
+        test_expect_err(src, vec![("Role", GraphQLKind::Enum)]);
+    }
+
+    #[test]
+    fn it_parses_bigint_fields() {
+        let src = "
+        type Account = { id: string; balance: bigint; }
+        type Ledger = { total?: bigint; }
+        ";
+        test(
             src,
+            indoc! { r#"
+            scalar BigInt
+            type Account {
+              id: String!
+              balance: BigInt!
+            }
+            type Ledger {
+              total: BigInt
+            }
+            "# },
             vec![
-                ("AnInput", GraphQLKind::Input),
-                ("Query", GraphQLKind::Object),
+                ("Account", GraphQLKind::Object),
+                ("Ledger", GraphQLKind::Object),
             ],
         );
     }
 
     #[test]
-    fn it_parses_array_fields() {
-        // Basic
+    fn it_maps_any_and_unknown_to_fallback_scalar() {
         let src = "
-        type User = { id: string; name: string; karma: number; }
-        type Player = { user: User[]; level: number[]; }
+        type Event = { id: string; payload: any; meta?: unknown; }
+        ";
+        test_with(
+            src,
+            indoc! { r#"
+            scalar JSON
+            type Event {
+              id: String!
+              payload: JSON!
+              meta: JSON
+            }
+            "# },
+            vec![("Event", GraphQLKind::Object)],
+            |gen| gen.options.any_scalar = Some("JSON".into()),
+        );
+    }
+
+    #[test]
+    fn it_fails_on_any_without_fallback_scalar() {
+        let src = "
+        type Event = { id: string; payload: any; }
+        ";
+        test_expect_err(src, vec![("Event", GraphQLKind::Object)]);
+
+        let src = "
+        type Event = { id: string; payload: unknown; }
+        ";
+        test_expect_err(src, vec![("Event", GraphQLKind::Object)]);
+    }
+
+    #[test]
+    fn it_collapses_conditional_types_with_equal_branches() {
+        let src = "
+        type User = {
+            id: string;
+            name: T extends string ? string : string;
+            tags?: T extends any[] ? string[] : (string)[];
+        }
         ";
         test(
             src,
@@ -801,66 +8654,362 @@ mod tests {
             type User {
               id: String!
               name: String!
-              karma: Int!
+              tags: [String!]
             }
-            type Player {
-              user: [User]!
-              level: [Int]!
+            "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+
+        let src = "
+        type User = {
+            id: T extends string ? string : number;
+        }
+        ";
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "Field `id` has a conditional type whose branches resolve to different types (String and Int), which can't be evaluated",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_refuses_coercions_in_strict_mode() {
+        let src = "
+        type Event = {
+            id: `event_${string}`;
+            payload: any;
+            kind: 'created' | 'deleted';
+        }
+        ";
+        let lenient = |gen: &mut CodeGenCtx| {
+            gen.options.any_scalar = Some("JSON".into());
+            gen.options.literal_unions_as_enums = false;
+        };
+        test_with(
+            src,
+            indoc! { r#"
+            scalar JSON
+            type Event {
+              id: String!
+              payload: JSON!
+              kind: String!
             }
             "# },
-            vec![
-                ("User", GraphQLKind::Object),
-                ("Player", GraphQLKind::Object),
-            ],
+            vec![("Event", GraphQLKind::Object)],
+            lenient,
         );
 
-        // Optional
+        let cases = [
+            (
+                "type Event = { id: `event_${string}`; }",
+                "Field `id` is a template literal, which strict mode doesn't coerce to String",
+            ),
+            (
+                "type Event = { payload: any; }",
+                "Field `payload` is `any`, which strict mode doesn't coerce to JSON",
+            ),
+            (
+                "type Event = { kind: 'created' | 'deleted'; }",
+                "Field `kind` is a union of string literals, which strict mode doesn't coerce to String",
+            ),
+            (
+                "type Event = { version: 1 | 2; }",
+                "Field `version` is a union of number literals, which strict mode doesn't coerce to Int",
+            ),
+        ];
+        for (src, expected) in cases.iter() {
+            let err = test_expect_err_with(src, vec![("Event", GraphQLKind::Object)], |gen| {
+                lenient(gen);
+                gen.options.strict = true;
+            });
+            assert_eq!(*expected, err.to_string());
+        }
+    }
+
+    #[test]
+    fn it_fails_on_never() {
         let src = "
-        type User = { id: string; name: string; karma: number; }
-        type Player = { user?: User[]; level?: number[]; }
+        type Event = { id: string; payload: never; }
+        ";
+        let err = test_expect_err(src, vec![("Event", GraphQLKind::Object)]);
+        assert_eq!(
+            "Field `payload` resolves to `never`, which has no GraphQL representation",
+            err.to_string()
+        );
+
+        let src = "
+        type Query = { events: () => Promise<never[]>; }
         ";
+        let err = test_expect_err(src, vec![("Query", GraphQLKind::Object)]);
+        assert_eq!(
+            "Field `events` resolves to `never`, which has no GraphQL representation",
+            err.to_string()
+        );
+    }
+
+    /// Builds a random type from `FRAGMENTS`, replacing each `T` with a nested type
+    /// until `depth` runs out
+    fn random_type(seed: &mut u64, depth: u32) -> String {
+        const FRAGMENTS: [&str; 40] = [
+            "string",
+            "number",
+            "boolean",
+            "bigint",
+            "any",
+            "unknown",
+            "never",
+            "symbol",
+            "object",
+            "void",
+            "null",
+            "undefined",
+            "User",
+            "Missing",
+            "Promise<T>",
+            "T[]",
+            "readonly T[]",
+            "[T, T]",
+            "T | null",
+            "T | undefined | null",
+            "T | T",
+            "T & T",
+            "{ a: T }",
+            "{ a?: T; b(x: T): T }",
+            "{ [k: string]: T }",
+            "{ foo }",
+            "(x: T, y?: T) => T",
+            "keyof T",
+            "typeof User",
+            "T extends string ? T : T",
+            "`x${string}`",
+            "\"a\" | \"b\"",
+            "1",
+            "Map<T, T>",
+            "Set<T>",
+            "Array<T, T>",
+            "Record<string, T>",
+            "User['id']",
+            "{ [K in keyof T]: T }",
+            "this",
+        ];
+        // xorshift, so runs are reproducible
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        let fragment = FRAGMENTS[(*seed % FRAGMENTS.len() as u64) as usize];
+        if depth == 0 {
+            return fragment.replace('T', "string");
+        }
+        fragment
+            .split('T')
+            .collect::<Vec<_>>()
+            .join(&random_type(seed, depth - 1))
+    }
+
+    #[test]
+    fn it_never_panics_on_arbitrary_types() {
+        const TEMPLATES: [&str; 5] = [
+            "type User = { id: string; }\ntype Query = { field: X; }",
+            "type User = { id: string; }\ntype Query = { method(arg: X): User; }",
+            "type User = { id: string; }\ntype Query = { method: (arg: X) => Promise<X>; }",
+            "type User = { id: string; }\ntype Query = X;",
+            "type User = { id: string; }\ninterface Query { field?: X }",
+        ];
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..2000 {
+            // A single random type per snippet, so it isn't hidden behind errors in others
+            let template = TEMPLATES[(seed % TEMPLATES.len() as u64) as usize];
+            let src = template.replace('X', &random_type(&mut seed, 2));
+            let result = std::panic::catch_unwind(|| {
+                let (prog, comments) = match parse_ts_with_comments(
+                    &src,
+                    r#"{ "syntax": "typescript", "tsx": false }"#,
+                ) {
+                    Ok(parsed) => parsed,
+                    Err(_) => return,
+                };
+                let manifest = vec![
+                    ("User".to_string(), GraphQLKind::Object),
+                    ("Query".to_string(), GraphQLKind::Object),
+                ];
+                let _ = generate_schema_with_comments(
+                    prog.module().unwrap(),
+                    comments,
+                    manifest.into_iter().collect::<HashMap<_, _>>(),
+                );
+            });
+            assert!(result.is_ok(), "panicked on: {}", src);
+        }
+    }
+
+    #[test]
+    fn it_accepts_string_literal_keys() {
+        let src = r#"
+        type User = {
+            "id": string;
+            /** @gqlName createdAt */
+            "created-at": string;
+            'posts'(first: number): string[];
+        }
+        type Query = { users(args: { "first": number }): User[]; }
+        "#;
         test(
             src,
             indoc! { r#"
             type User {
               id: String!
-              name: String!
-              karma: Int!
+              createdAt: String!
+              posts(first: Int!): [String!]
             }
-            type Player {
-              user: [User]
-              level: [Int]
+            type Query {
+              users(first: Int!): [User!]
             }
             "# },
             vec![
                 ("User", GraphQLKind::Object),
-                ("Player", GraphQLKind::Object),
+                ("Query", GraphQLKind::Object),
             ],
         );
 
-        // Nested
+        let src = r#"type User = { "created-at": string; }"#;
+        let err = test_expect_err(src, vec![("User", GraphQLKind::Object)]);
+        assert_eq!(
+            "`created-at` is not a valid GraphQL name (check: User)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_validates_emitted_names() {
+        let src = r#"
+        type User$ = {
+            "2fa-enabled": boolean;
+            status: "in-progress" | "done";
+            posts(args: { "sort-by": string }): string[];
+            find($id: string, limit: number): string;
+        }
+        "#;
+        let manifest = || vec![("User$", GraphQLKind::Object)];
+        let err = test_expect_err(src, manifest());
+        assert_eq!(
+            "`User$` is not a valid GraphQL name (check: User$)",
+            err.to_string()
+        );
+
+        let err = test_expect_err(
+            r#"type User = { "2fa-enabled": boolean; }"#,
+            vec![("User", GraphQLKind::Object)],
+        );
+        assert_eq!(
+            "`2fa-enabled` is not a valid GraphQL name (check: User)",
+            err.to_string()
+        );
+
+        test_with(
+            src,
+            indoc! { r#"
+            enum User_StatusEnum {
+              in_progress
+              done
+            }
+            type User_ {
+              _2fa_enabled: Boolean!
+              status: User_StatusEnum!
+              posts(sort_by: String!): [String!]
+              find(_id: String!, limit: Int!): String
+            }
+            "# },
+            manifest(),
+            |gen| gen.options.sanitize_names = true,
+        );
+    }
+
+    #[test]
+    fn it_fails_on_computed_keys() {
+        let src = "type Users = { [Symbol.iterator]: () => string; }";
+        let err = test_expect_err(src, vec![("Users", GraphQLKind::Object)]);
+        assert_eq!(
+            "Computed keys like `[Symbol.iterator]` are not supported, fields must be named with identifiers or string literals (check: Users)",
+            err.to_string()
+        );
+
+        let src = r#"type Users = { ["id"]: string; }"#;
+        let err = test_expect_err(src, vec![("Users", GraphQLKind::Object)]);
+        assert!(err.to_string().starts_with("Computed keys"));
+    }
+
+    #[test]
+    fn it_fails_on_untyped_properties() {
+        let err = test_expect_err("type T = { foo }", vec![("T", GraphQLKind::Object)]);
+        assert_eq!(
+            "Field `foo` of type T has no type annotation",
+            err.to_string()
+        );
+
+        let src = "type Query = { users(args: { first }): string[] }";
+        let err = test_expect_err(src, vec![("Query", GraphQLKind::Object)]);
+        assert_eq!(
+            "Arg `first` of field `users` has no type annotation",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn it_parses_classes() {
         let src = "
-        type User = { id: string[][]; name: string; karma: number; }
-        type Player = { user?: User[][]; level?: number[][]; }
+        /** A user */
+        export class User {
+            /** Unique id */
+            id: string;
+            name?: string;
+            private password: string;
+            static count: number;
+            constructor(id: string) {}
+            get displayName(): string { return this.name; }
+            set displayName(name: string) {}
+            posts(first: number, after?: string): Promise<string[]> { return []; }
+        }
         ";
         test(
             src,
             indoc! { r#"
             type User {
-              id: [[String]]!
-              name: String!
-              karma: Int!
-            }
-            type Player {
-              user: [[User]]
-              level: [[Int]]
+              "Unique id"
+              id: String!
+              name: String
+              displayName: String!
+              posts(first: Int!, after: String): [String!]!
             }
             "# },
+            vec![("User", GraphQLKind::Object)],
+        );
+    }
+
+    #[test]
+    fn it_resolves_typeof_to_manifest_types() {
+        let src = "
+        const User = { id: 1 };
+        type User = { id: number; };
+        type Session = { user: typeof User; owner?: typeof User; };
+        ";
+        test(
+            src,
+            "type User {\n  id: Int!\n}\ntype Session {\n  user: User!\n  owner: User\n}\n",
             vec![
                 ("User", GraphQLKind::Object),
-                ("Player", GraphQLKind::Object),
+                ("Session", GraphQLKind::Object),
             ],
         );
+
+        let src = "
+        const defaults = { id: 1 };
+        type Session = { user: typeof defaults; };
+        ";
+        let err = test_expect_err(src, vec![("Session", GraphQLKind::Object)]);
+        assert_eq!(
+            "Field `user` uses `typeof defaults`, which can only be resolved when defaults is a type in the manifest",
+            err.to_string()
+        );
     }
 
     #[cfg(test)]
@@ -868,11 +9017,10 @@ mod tests {
         use super::*;
 
         #[test]
-        fn it_parses_fields_with_args() {
-            // Basic
+        fn it_parses_multiple_params_as_args() {
             let src = "
         type User = { id: string; name: string; karma: number; }
-        type Query = { findUser: (args: { id?: string, name?: string, test: string[] }) => Promise<User[]>; }
+        type Query = { findUser: (id: string, limit: number) => Promise<User>; }
         ";
             test(
                 src,
@@ -883,7 +9031,7 @@ mod tests {
               karma: Int!
             }
             type Query {
-              findUser(id: String, name: String, test: [String]!): [User]!
+              findUser(id: String!, limit: Int!): User!
             }
             "# },
                 vec![
@@ -892,11 +9040,10 @@ mod tests {
                 ],
             );
 
-            // With pre-defined input
+            // Inlined type literals become inputs named after their param
             let src = "
         type User = { id: string; name: string; karma: number; }
-        type FindUserInput = { name: string, id?: string }
-        type Query = { findUser: (args: { input: FindUserInput }) => Promise<User | null>; }
+        type Query = { findUser: (args: { name: string }, woops: { karma: number }) => Promise<User>; }
         ";
             test(
                 src,
@@ -906,26 +9053,27 @@ mod tests {
               name: String!
               karma: Int!
             }
-            input FindUserInput {
+            input FindUserInputArgs {
               name: String!
-              id: String
+            }
+            input FindUserInputWoops {
+              karma: Int!
             }
             type Query {
-              findUser(input: FindUserInput!): User
+              findUser(args: FindUserInputArgs!, woops: FindUserInputWoops!): User!
             }
             "# },
                 vec![
                     ("User", GraphQLKind::Object),
-                    ("FindUserInput", GraphQLKind::Input),
                     ("Query", GraphQLKind::Object),
                 ],
             );
 
-            // Mix and match
+            // A single param that isn't an object literal is an arg of its own
             let src = "
         type User = { id: string; name: string; karma: number; }
         type FindUserInput = { name: string, id?: string }
-        type Query = { findUser: (args: { input: FindUserInput, karma?: number }) => Promise<User>; }
+        type Query = { findUser: (input: FindUserInput) => Promise<User>; }
         ";
             test(
                 src,
@@ -940,7 +9088,7 @@ mod tests {
               id: String
             }
             type Query {
-              findUser(input: FindUserInput!, karma: Int): User!
+              findUser(input: FindUserInput!): User!
             }
             "# },
                 vec![
@@ -952,11 +9100,35 @@ mod tests {
         }
 
         #[test]
-        fn it_should_fail_when_given_multiple_args() {
-            // Inlined type literal
+        fn it_parses_optional_params_as_nullable_args() {
             let src = "
         type User = { id: string; name: string; karma: number; }
-        type Query = { findUser: (args: { name: string }, woops: { karma: number }) => Promise<User>; }
+        type Query = { findUsers: (id: string, limit?: number) => Promise<User[]>; }
+        ";
+            test(
+                src,
+                indoc! { r#"
+            type User {
+              id: String!
+              name: String!
+              karma: Int!
+            }
+            type Query {
+              findUsers(id: String!, limit: Int): [User!]!
+            }
+            "# },
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                ],
+            );
+        }
+
+        #[test]
+        fn it_should_fail_on_rest_params() {
+            let src = "
+        type User = { id: string; name: string; karma: number; }
+        type Query = { findUser: (id: string, ...rest: string[]) => Promise<User>; }
         ";
             test_expect_err(
                 src,
@@ -1057,6 +9229,324 @@ mod tests {
             );
         }
 
+        #[test]
+        fn it_always_suffixes_input_names_when_configured() {
+            let src = "
+            type User = { id: string; name: string; karma: number; }
+            type Query = { findUser: (args: { user: { name?: string } }) => Promise<User>; }
+            ";
+            let mani = || {
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                ]
+            };
+            let user = indoc! { r#"
+                type User {
+                  id: String!
+                  name: String!
+                  karma: Int!
+                }
+                "# };
+
+            test(
+                src,
+                &format!(
+                    "{}{}",
+                    user,
+                    indoc! { r#"
+                    input FindUserInput {
+                      name: String
+                    }
+                    type Query {
+                      findUser(user: FindUserInput!): User!
+                    }
+                    "# }
+                ),
+                mani(),
+            );
+            test_with(
+                src,
+                &format!(
+                    "{}{}",
+                    user,
+                    indoc! { r#"
+                    input FindUserInputUser {
+                      name: String
+                    }
+                    type Query {
+                      findUser(user: FindUserInputUser!): User!
+                    }
+                    "# }
+                ),
+                mani(),
+                |gen| gen.options.always_suffix_input_names = true,
+            );
+
+            // With sibling args both modes produce the same name
+            let src = "
+            type User = { id: string; name: string; karma: number; }
+            type Query = { findUser: (args: { user: { name?: string }, karma?: number }) => Promise<User>; }
+            ";
+            let expected = format!(
+                "{}{}",
+                user,
+                indoc! { r#"
+                input FindUserInputUser {
+                  name: String
+                }
+                type Query {
+                  findUser(user: FindUserInputUser!, karma: Int): User!
+                }
+                "# }
+            );
+            test(src, &expected, mani());
+            test_with(src, &expected, mani(), |gen| {
+                gen.options.always_suffix_input_names = true
+            });
+        }
+
+        #[test]
+        fn it_deduplicates_identical_synthesized_types() {
+            let src = "
+        type User = { id: string; name: string; karma: number; }
+        type Query = { findUser: (args: { id: string }) => Promise<{ user: User, success: boolean }>; }
+        type Mutation = { findUser: (args: { name: string }) => Promise<{ user: User, success: boolean }>; }
+        ";
+            test(
+                src,
+                indoc! { r#"
+                type User {
+                  id: String!
+                  name: String!
+                  karma: Int!
+                }
+                type FindUserOutput {
+                  user: User!
+                  success: Boolean!
+                }
+                type Query {
+                  findUser(id: String!): FindUserOutput!
+                }
+                type Mutation {
+                  findUser(name: String!): FindUserOutput!
+                }
+                "# },
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                    ("Mutation", GraphQLKind::Object),
+                ],
+            );
+        }
+
+        #[test]
+        fn it_should_fail_on_conflicting_synthesized_types() {
+            let src = "
+        type User = { id: string; name: string; karma: number; }
+        type Query = { findUser: (args: { id: string }) => Promise<{ user: User, success: boolean }>; }
+        type Mutation = { findUser: (args: { name: string }) => Promise<{ user: User }>; }
+        ";
+            test_expect_err(
+                src,
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                    ("Mutation", GraphQLKind::Object),
+                ],
+            );
+        }
+
+        #[test]
+        fn it_emits_nullable_object_fields_in_loose_mode() {
+            let src = "
+        type User = { id: string; tags: string[]; friends?: User[]; }
+        type FindUserInput = { id: string }
+        type Query = { findUser: (args: { input: FindUserInput, name?: string }) => Promise<User>; }
+        ";
+            test_with(
+                src,
+                indoc! { r#"
+            type User {
+              id: String
+              tags: [String]
+              friends: [User]
+            }
+            input FindUserInput {
+              id: String!
+            }
+            type Query {
+              findUser(input: FindUserInput!, name: String): User
+            }
+            "# },
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("FindUserInput", GraphQLKind::Input),
+                    ("Query", GraphQLKind::Object),
+                ],
+                |gen| gen.options.force_nullable = true,
+            );
+        }
+
+        #[test]
+        fn it_should_identify_async_iterable_return_type() {
+            let mani = || {
+                vec![
+                    ("Event", GraphQLKind::Object),
+                    ("Subscription", GraphQLKind::Object),
+                ]
+            };
+            let expected = indoc! { r#"
+                type Event {
+                  id: String!
+                }
+                type Subscription {
+                  onEvent(id: String!): Event!
+                }
+                "# };
+
+            let src = "
+        type Event = { id: string; }
+        type Subscription = { onEvent: (args: { id: string }) => AsyncIterable<Event>; }
+        ";
+            test(src, expected, mani());
+
+            let src = "
+        type Event = { id: string; }
+        type Subscription = { onEvent: (args: { id: string }) => AsyncGenerator<Event, void, unknown>; }
+        ";
+            test(src, expected, mani());
+
+            let src = "
+        type Event = { id: string; }
+        type Subscription = { onEvent: (args: { id: string }) => AsyncIterable<Event, void>; }
+        ";
+            test_expect_err(src, mani());
+        }
+
+        #[test]
+        fn it_maps_index_signatures_to_scalars() {
+            let src = "
+        type User = { id: string; }
+        type Scores = { [key: string]: number }
+        type Query = { findUsers: (args: { filter: { [key: string]: string } }) => Promise<{ users: User[], scores: Scores }>; }
+        ";
+            let mani = || {
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Scores", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                ]
+            };
+            test_with(
+                src,
+                indoc! { r#"
+            type User {
+              id: String!
+            }
+            scalar JSON
+            type FindUsersOutput {
+              users: [User!]!
+              scores: JSON!
+            }
+            type Query {
+              findUsers(filter: JSON!): FindUsersOutput!
+            }
+            "# },
+                mani(),
+                |gen| gen.options.index_signature_scalar = Some("JSON".into()),
+            );
+
+            let err = test_expect_err(src, mani());
+            assert!(err.to_string().contains("Scores"));
+        }
+
+        #[test]
+        fn it_never_declares_built_in_scalars() {
+            let src = "
+        type Query = {
+            data: any;
+            count: bigint;
+            tags: { [key: string]: string };
+        }
+        ";
+            test_with(
+                src,
+                indoc! { r#"
+            type Query {
+              data: String!
+              count: String!
+              tags: String!
+            }
+            "# },
+                vec![("Query", GraphQLKind::Object)],
+                |gen| {
+                    gen.options.any_scalar = Some("String".into());
+                    gen.options.bigint_scalar = "String".into();
+                    gen.options.index_signature_scalar = Some("String".into());
+                },
+            );
+
+            let src = "
+        type String = { [key: string]: string }
+        type Query = { tags: String; }
+        ";
+            test_with(
+                src,
+                indoc! { r#"
+            scalar JSON
+            type Query {
+              tags: JSON!
+            }
+            "# },
+                vec![
+                    ("String", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                ],
+                |gen| gen.options.index_signature_scalar = Some("JSON".into()),
+            );
+        }
+
+        #[test]
+        fn it_parses_method_signatures() {
+            let mani = || {
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                ]
+            };
+            let expected = indoc! { r#"
+                type User {
+                  id: String!
+                }
+                type FindUserOutput {
+                  user: User!
+                }
+                type Query {
+                  findUser(id: String!): FindUserOutput
+                  users(limit: Int): [User!]!
+                }
+                "# };
+
+            let src = "
+        type User = { id: string; }
+        type Query = {
+            findUser: (args: { id: string }) => Promise<{ user: User } | null>;
+            users: (limit?: number) => Promise<User[]>;
+        }
+        ";
+            test(src, expected, mani());
+
+            let src = "
+        type User = { id: string; }
+        type Query = {
+            findUser(args: { id: string }): Promise<{ user: User } | null>;
+            users(limit?: number): Promise<User[]>;
+        }
+        ";
+            test(src, expected, mani());
+        }
+
         #[test]
         fn it_should_fail_when_an_arg_isnt_an_input() {
             let src = "
@@ -1224,7 +9714,7 @@ mod tests {
                   karma: Int!
                 }
                 type Mutation {
-                  updateUser(user: UpdateUserInput!): [UpdateUserOutput]
+                  updateUser(user: UpdateUserInput!): [UpdateUserOutput!]
                 }
                 "# },
                 vec![
@@ -1265,5 +9755,40 @@ mod tests {
                 ],
             );
         }
+
+        #[test]
+        fn it_makes_every_arg_of_an_optional_args_object_nullable() {
+            let src = "
+            type User = { id: string; name: string; }
+            type Query = {
+                users: (args?: {
+                    first: number;
+                    after?: string;
+                    /** @default 0 */
+                    offset?: number;
+                    filter: { name: string; };
+                }) => Promise<User[]>;
+            }
+            ";
+            test(
+                src,
+                indoc! { r#"
+                type User {
+                  id: String!
+                  name: String!
+                }
+                input UsersInputFilter {
+                  name: String!
+                }
+                type Query {
+                  users(first: Int, after: String, offset: Int! = 0, filter: UsersInputFilter): [User!]!
+                }
+                "# },
+                vec![
+                    ("User", GraphQLKind::Object),
+                    ("Query", GraphQLKind::Object),
+                ],
+            );
+        }
     }
 }
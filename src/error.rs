@@ -0,0 +1,46 @@
+use std::{error::Error, fmt, ops::Range};
+
+use swc_common::Span;
+
+/// Error raised while generating the schema of a declaration or field, carrying the
+/// span of that node in the Typescript source
+#[derive(Debug)]
+pub struct SourceError {
+    message: String,
+    span: Span,
+}
+
+impl SourceError {
+    /// Attaches `span` to `error`, unless it already points at a (nested) node
+    pub(crate) fn wrap(span: Span, error: anyhow::Error) -> anyhow::Error {
+        if error.is::<SourceError>() || span.is_dummy() {
+            return error;
+        }
+        anyhow::Error::new(SourceError {
+            message: format!("{:#}", error),
+            span,
+        })
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Byte range of the node in the source it was parsed from, `parse_ts` and friends
+    /// parse each source into a fresh `SourceMap` so it starts at 0
+    pub fn range(&self) -> Range<usize> {
+        self.span.lo.0 as usize..self.span.hi.0 as usize
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for SourceError {}
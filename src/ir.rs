@@ -0,0 +1,746 @@
+use std::{collections::HashMap, fmt};
+
+use anyhow::Result;
+use apollo_parser::{ast, Parser};
+use serde::Serialize;
+use swc::SwcComments;
+use swc_ecmascript::ast::Module;
+
+use crate::{
+    generate_schema, generate_schema_with_options, CodeGenOptions, GraphQLKind, ManifestEntry,
+    RootOperation,
+};
+
+/// Structured description of a generated schema, shaped like GraphQL introspection
+/// results so it can be serialized to JSON for codegen pipelines
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaIR {
+    /// Names of the root types, from the `schema` definition or else the objects named
+    /// `Query`, `Mutation` and `Subscription`
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: Vec<TypeIR>,
+}
+
+impl SchemaIR {
+    /// Returns the name of the root type of `operation`
+    pub fn root_type(&self, operation: RootOperation) -> Option<&str> {
+        match operation {
+            RootOperation::Query => self.query_type.as_deref(),
+            RootOperation::Mutation => self.mutation_type.as_deref(),
+            RootOperation::Subscription => self.subscription_type.as_deref(),
+        }
+    }
+
+    /// Returns true if `name` is the root type of an operation
+    pub fn is_root_type(&self, name: &str) -> bool {
+        [
+            RootOperation::Query,
+            RootOperation::Mutation,
+            RootOperation::Subscription,
+        ]
+        .iter()
+        .any(|operation| self.root_type(*operation) == Some(name))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TypeKindIR {
+    Object,
+    InputObject,
+    Interface,
+    Union,
+    Enum,
+    Scalar,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeIR {
+    pub kind: TypeKindIR,
+    pub name: String,
+    pub description: Option<String>,
+    /// Interfaces implemented by an object
+    pub interfaces: Vec<String>,
+    /// Fields of an object or interface
+    pub fields: Vec<FieldIR>,
+    /// Fields of an input
+    pub input_fields: Vec<InputValueIR>,
+    pub enum_values: Vec<EnumValueIR>,
+    /// Members of a union
+    pub possible_types: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FieldIR {
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<InputValueIR>,
+    #[serde(rename = "type")]
+    pub ty: TypeRefIR,
+}
+
+/// An argument or input field
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct InputValueIR {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: TypeRefIR,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EnumValueIR {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TypeRefIR {
+    Named {
+        name: String,
+    },
+    List {
+        #[serde(rename = "ofType")]
+        of_type: Box<TypeRefIR>,
+    },
+    NonNull {
+        #[serde(rename = "ofType")]
+        of_type: Box<TypeRefIR>,
+    },
+}
+
+impl fmt::Display for TypeRefIR {
+    /// Writes the type as it appears in SDL, like `[User!]!`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Named { name } => write!(f, "{}", name),
+            Self::List { of_type } => write!(f, "[{}]", of_type),
+            Self::NonNull { of_type } => write!(f, "{}!", of_type),
+        }
+    }
+}
+
+/// A field of a root type, for client codegen that only needs the operations a schema
+/// exposes
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInfo {
+    pub operation: RootOperation,
+    pub name: String,
+    pub args: Vec<OperationArg>,
+    /// The return type as written in SDL, like `[User!]!`
+    pub return_type: String,
+    pub nullable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OperationArg {
+    pub name: String,
+    /// The type as written in SDL, like `Int!`
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub nullable: bool,
+}
+
+/// Lists the fields of the query, mutation and subscription root types of `ir`, in that
+/// order, including those added by `extend type` blocks
+pub fn operation_index(ir: &SchemaIR) -> Vec<OperationInfo> {
+    let roots = [
+        RootOperation::Query,
+        RootOperation::Mutation,
+        RootOperation::Subscription,
+    ];
+    roots
+        .iter()
+        .filter_map(|operation| {
+            let name = ir.root_type(*operation)?;
+            let ty = ir
+                .types
+                .iter()
+                .find(|ty| ty.kind == TypeKindIR::Object && ty.name == name)?;
+            Some(ty.fields.iter().map(move |field| {
+                OperationInfo {
+                    operation: *operation,
+                    name: field.name.clone(),
+                    args: field
+                        .args
+                        .iter()
+                        .map(|arg| OperationArg {
+                            name: arg.name.clone(),
+                            ty: arg.ty.to_string(),
+                            nullable: !matches!(arg.ty, TypeRefIR::NonNull { .. }),
+                        })
+                        .collect(),
+                    return_type: field.ty.to_string(),
+                    nullable: !matches!(field.ty, TypeRefIR::NonNull { .. }),
+                }
+            }))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Names, kinds and fields of the types in a generated schema, including the inputs and
+/// outputs synthesized from inline object literals. Scalars are left out
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SchemaMeta {
+    pub types: Vec<TypeMeta>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeMeta {
+    pub name: String,
+    pub kind: GraphQLKind,
+    /// Field names of objects, inputs and interfaces, the values of enums or the members
+    /// of unions
+    pub fields: Vec<String>,
+}
+
+/// Like `generate_schema`, but also describes the generated types, e.g. to scaffold
+/// resolvers for the fields of `Query`
+pub fn generate_schema_with_meta<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+) -> Result<(String, SchemaMeta)> {
+    let sdl = generate_schema_with_options(prog, SwcComments::default(), manifest, options)?;
+    let meta = schema_meta(&sdl)?;
+    Ok((sdl, meta))
+}
+
+/// Returns the `SchemaMeta` of generated SDL
+pub fn schema_meta(sdl: &str) -> Result<SchemaMeta> {
+    Ok(type_index(&schema_ir(sdl)?))
+}
+
+/// Returns the `SchemaMeta` of the types of `ir`
+pub fn type_index(ir: &SchemaIR) -> SchemaMeta {
+    let types = ir
+        .types
+        .iter()
+        .filter_map(|ty| {
+            let (kind, fields) = match ty.kind {
+                TypeKindIR::Object => (GraphQLKind::Object, names(&ty.fields)),
+                TypeKindIR::Interface => (GraphQLKind::Interface, names(&ty.fields)),
+                TypeKindIR::InputObject => (
+                    GraphQLKind::Input,
+                    ty.input_fields.iter().map(|f| f.name.clone()).collect(),
+                ),
+                TypeKindIR::Enum => (
+                    GraphQLKind::Enum,
+                    ty.enum_values.iter().map(|v| v.name.clone()).collect(),
+                ),
+                TypeKindIR::Union => (GraphQLKind::Union, ty.possible_types.clone()),
+                TypeKindIR::Scalar => return None,
+            };
+            Some(TypeMeta {
+                name: ty.name.clone(),
+                kind,
+                fields,
+            })
+        })
+        .collect();
+
+    SchemaMeta { types }
+}
+
+fn names(fields: &[FieldIR]) -> Vec<String> {
+    fields.iter().map(|f| f.name.clone()).collect()
+}
+
+/// Like `generate_schema`, but returns the schema as a `SchemaIR` instead of SDL
+pub fn generate_schema_ir<K: Into<ManifestEntry>>(
+    prog: Module,
+    manifest: HashMap<String, K>,
+) -> Result<SchemaIR> {
+    schema_ir(&generate_schema(prog, manifest)?)
+}
+
+/// Like `generate_schema_ir`, but reads JSDoc tags from `comments` and takes every
+/// setting of the generator from `options`
+pub fn generate_schema_ir_with_options<K: Into<ManifestEntry>>(
+    prog: Module,
+    comments: SwcComments,
+    manifest: HashMap<String, K>,
+    options: CodeGenOptions,
+) -> Result<SchemaIR> {
+    schema_ir(&generate_schema_with_options(
+        prog, comments, manifest, options,
+    )?)
+}
+
+/// Builds the `SchemaIR` of generated SDL, which is parsed again because the encoder's
+/// types don't expose their contents. The fields of `extend type` blocks are folded into
+/// the type they extend
+pub fn schema_ir(sdl: &str) -> Result<SchemaIR> {
+    let tree = Parser::new(sdl).parse();
+    if let Some(err) = tree.errors().next() {
+        return Err(anyhow::anyhow!(
+            "Generated schema is invalid: {} at {}",
+            err.message(),
+            err.index()
+        ));
+    }
+
+    let mut extensions = Vec::new();
+    let mut roots = Vec::new();
+    let mut types = tree
+        .document()
+        .definitions()
+        .filter_map(|def| match def {
+            ast::Definition::ObjectTypeDefinition(def) => {
+                let mut ty = TypeIR::new(TypeKindIR::Object, def.name(), def.description());
+                ty.interfaces = interfaces(def.implements_interfaces());
+                ty.fields = fields(def.fields_definition());
+                Some(ty)
+            }
+            ast::Definition::ObjectTypeExtension(def) => {
+                extensions.push(def);
+                None
+            }
+            ast::Definition::SchemaDefinition(def) => {
+                roots.extend(def.root_operation_type_definitions().filter_map(|root| {
+                    let operation = root.operation_type()?;
+                    let operation = if operation.query_token().is_some() {
+                        RootOperation::Query
+                    } else if operation.mutation_token().is_some() {
+                        RootOperation::Mutation
+                    } else {
+                        RootOperation::Subscription
+                    };
+                    Some((operation, name_of(root.named_type()?.name())))
+                }));
+                None
+            }
+            ast::Definition::InterfaceTypeDefinition(def) => {
+                let mut ty = TypeIR::new(TypeKindIR::Interface, def.name(), def.description());
+                ty.fields = fields(def.fields_definition());
+                Some(ty)
+            }
+            ast::Definition::UnionTypeDefinition(def) => {
+                let mut ty = TypeIR::new(TypeKindIR::Union, def.name(), def.description());
+                ty.possible_types = def
+                    .union_member_types()
+                    .map(|members| members.named_types().map(|ty| name_of(ty.name())).collect())
+                    .unwrap_or_default();
+                Some(ty)
+            }
+            ast::Definition::InputObjectTypeDefinition(def) => {
+                let mut ty = TypeIR::new(TypeKindIR::InputObject, def.name(), def.description());
+                ty.input_fields = def
+                    .input_fields_definition()
+                    .map(|fields| fields.input_value_definitions().map(input_value).collect())
+                    .unwrap_or_default();
+                Some(ty)
+            }
+            ast::Definition::EnumTypeDefinition(def) => {
+                let mut ty = TypeIR::new(TypeKindIR::Enum, def.name(), def.description());
+                ty.enum_values = def
+                    .enum_values_definition()
+                    .map(|values| {
+                        values
+                            .enum_value_definitions()
+                            .map(|value| EnumValueIR {
+                                name: value
+                                    .enum_value()
+                                    .map(|v| v.text().to_string())
+                                    .unwrap_or_default(),
+                                description: description(value.description()),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(ty)
+            }
+            ast::Definition::ScalarTypeDefinition(def) => Some(TypeIR::new(
+                TypeKindIR::Scalar,
+                def.name(),
+                def.description(),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<TypeIR>>();
+
+    for def in extensions {
+        let name = name_of(def.name());
+        let base = types
+            .iter()
+            .position(|ty| ty.kind == TypeKindIR::Object && ty.name == name);
+        let ty = match base {
+            Some(idx) => &mut types[idx],
+            None => {
+                types.push(TypeIR::new(TypeKindIR::Object, def.name(), None));
+                types.last_mut().unwrap()
+            }
+        };
+        ty.interfaces
+            .extend(interfaces(def.implements_interfaces()));
+        ty.fields.extend(fields(def.fields_definition()));
+    }
+
+    // Without a `schema` definition, root types go by their default names
+    let root = |operation: RootOperation, default: &str| {
+        if roots.is_empty() {
+            types
+                .iter()
+                .find(|ty| ty.kind == TypeKindIR::Object && ty.name == default)
+                .map(|ty| ty.name.clone())
+        } else {
+            roots
+                .iter()
+                .find(|(op, _)| *op == operation)
+                .map(|(_, name)| name.clone())
+        }
+    };
+    Ok(SchemaIR {
+        query_type: root(RootOperation::Query, "Query"),
+        mutation_type: root(RootOperation::Mutation, "Mutation"),
+        subscription_type: root(RootOperation::Subscription, "Subscription"),
+        types,
+    })
+}
+
+impl TypeIR {
+    fn new(kind: TypeKindIR, name: Option<ast::Name>, desc: Option<ast::Description>) -> Self {
+        Self {
+            kind,
+            name: name_of(name),
+            description: description(desc),
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            input_fields: Vec::new(),
+            enum_values: Vec::new(),
+            possible_types: Vec::new(),
+        }
+    }
+}
+
+fn name_of(name: Option<ast::Name>) -> String {
+    name.map(|name| name.text().to_string()).unwrap_or_default()
+}
+
+/// Returns the text of a description, with the indentation of block strings removed
+fn description(desc: Option<ast::Description>) -> Option<String> {
+    let text: String = desc?.string_value()?.into();
+    Some(
+        text.lines()
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .join("\n")
+            .trim()
+            .to_string(),
+    )
+}
+
+fn interfaces(implements: Option<ast::ImplementsInterfaces>) -> Vec<String> {
+    implements
+        .map(|implements| {
+            implements
+                .named_types()
+                .map(|ty| name_of(ty.name()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn fields(fields: Option<ast::FieldsDefinition>) -> Vec<FieldIR> {
+    fields
+        .map(|fields| {
+            fields
+                .field_definitions()
+                .map(|field| FieldIR {
+                    name: name_of(field.name()),
+                    description: description(field.description()),
+                    args: field
+                        .arguments_definition()
+                        .map(|args| args.input_value_definitions().map(input_value).collect())
+                        .unwrap_or_default(),
+                    ty: type_ref(field.ty()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn input_value(value: ast::InputValueDefinition) -> InputValueIR {
+    InputValueIR {
+        name: name_of(value.name()),
+        description: description(value.description()),
+        ty: type_ref(value.ty()),
+    }
+}
+
+fn type_ref(ty: Option<ast::Type>) -> TypeRefIR {
+    match ty {
+        Some(ast::Type::NonNullType(ty)) => {
+            let inner = match (ty.named_type(), ty.list_type()) {
+                (Some(named), _) => Some(ast::Type::NamedType(named)),
+                (None, Some(list)) => Some(ast::Type::ListType(list)),
+                (None, None) => None,
+            };
+            TypeRefIR::NonNull {
+                of_type: Box::new(type_ref(inner)),
+            }
+        }
+        Some(ast::Type::ListType(ty)) => TypeRefIR::List {
+            of_type: Box::new(type_ref(ty.ty())),
+        },
+        Some(ast::Type::NamedType(ty)) => TypeRefIR::Named {
+            name: name_of(ty.name()),
+        },
+        None => TypeRefIR::Named {
+            name: String::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_ts;
+
+    #[test]
+    fn it_describes_generated_types() {
+        let prog = parse_ts(
+            "
+            type User = {
+                id: string;
+                role: 'admin' | 'member';
+            }
+            type Query = {
+                user: (args: { id: string }) => Promise<User | null>;
+                search: (args: { filter: { name: string }; first: number }) => Promise<{ total: number; users: User[] }>;
+            }
+            ",
+            "{ \"syntax\": \"typescript\" }",
+        )
+        .unwrap();
+        let mut manifest = HashMap::new();
+        manifest.insert("User".to_string(), GraphQLKind::Object);
+        manifest.insert("Query".to_string(), GraphQLKind::Object);
+
+        let (_, meta) =
+            generate_schema_with_meta(prog.module().unwrap(), manifest, CodeGenOptions::default())
+                .unwrap();
+        let expected = serde_json::json!({
+            "types": [
+                { "name": "UserRoleEnum", "kind": "Enum", "fields": ["admin", "member"] },
+                { "name": "User", "kind": "Object", "fields": ["id", "role"] },
+                { "name": "SearchInputFilter", "kind": "Input", "fields": ["name"] },
+                { "name": "SearchOutput", "kind": "Object", "fields": ["total", "users"] },
+                { "name": "Query", "kind": "Object", "fields": ["user", "search"] }
+            ]
+        });
+        assert_eq!(expected, serde_json::to_value(&meta).unwrap());
+    }
+
+    #[test]
+    fn it_describes_unions_and_extensions() {
+        let meta = schema_meta(
+            "
+            type User { id: String! }
+            type Bot { id: String! }
+            union Actor = User | Bot
+            type Query { me: Actor }
+            extend type Query { users: [User!]! }
+            ",
+        )
+        .unwrap();
+        let expected = serde_json::json!({
+            "types": [
+                { "name": "User", "kind": "Object", "fields": ["id"] },
+                { "name": "Bot", "kind": "Object", "fields": ["id"] },
+                { "name": "Actor", "kind": "Union", "fields": ["User", "Bot"] },
+                { "name": "Query", "kind": "Object", "fields": ["me", "users"] }
+            ]
+        });
+        assert_eq!(expected, serde_json::to_value(&meta).unwrap());
+    }
+
+    #[test]
+    fn it_indexes_root_operations() {
+        let prog = parse_ts(
+            "
+            type User = {
+                id: string;
+            }
+            type Query = {
+                user: (args: { id: string; withDeleted?: boolean }) => Promise<User | null>;
+                users(first: number): Promise<User[]>;
+            }
+            ",
+            "{ \"syntax\": \"typescript\" }",
+        )
+        .unwrap();
+        let mut manifest = HashMap::new();
+        manifest.insert("User".to_string(), GraphQLKind::Object);
+        manifest.insert("Query".to_string(), GraphQLKind::Object);
+
+        let ir = generate_schema_ir(prog.module().unwrap(), manifest).unwrap();
+        let expected = serde_json::json!([
+            {
+                "operation": "Query",
+                "name": "user",
+                "args": [
+                    { "name": "id", "type": "String!", "nullable": false },
+                    { "name": "withDeleted", "type": "Boolean", "nullable": true }
+                ],
+                "returnType": "User",
+                "nullable": true
+            },
+            {
+                "operation": "Query",
+                "name": "users",
+                "args": [{ "name": "first", "type": "Int!", "nullable": false }],
+                "returnType": "[User!]!",
+                "nullable": false
+            }
+        ]);
+        assert_eq!(
+            expected,
+            serde_json::to_value(operation_index(&ir)).unwrap()
+        );
+
+        // Root types may be renamed by the schema definition and extended
+        let ir = schema_ir(
+            "
+            schema { query: RootQuery }
+            type Query { ignored: String }
+            type RootQuery { me: String }
+            extend type RootQuery { users: [String!]! }
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["me", "users"],
+            operation_index(&ir)
+                .into_iter()
+                .map(|op| op.name)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_serializes_the_schema_ir() {
+        let prog = parse_ts(
+            "
+            type User = {
+                id: string;
+                tags?: string[];
+                friends(first: number): Promise<User[]>;
+            }
+            ",
+            "{ \"syntax\": \"typescript\" }",
+        )
+        .unwrap();
+        let mut manifest = HashMap::new();
+        manifest.insert("User".to_string(), GraphQLKind::Object);
+
+        let ir = generate_schema_ir(prog.module().unwrap(), manifest).unwrap();
+        let expected = serde_json::json!({
+            "queryType": null,
+            "mutationType": null,
+            "subscriptionType": null,
+            "types": [{
+                "kind": "OBJECT",
+                "name": "User",
+                "description": null,
+                "interfaces": [],
+                "fields": [
+                    {
+                        "name": "id",
+                        "description": null,
+                        "args": [],
+                        "type": { "kind": "NON_NULL", "ofType": { "kind": "NAMED", "name": "String" } }
+                    },
+                    {
+                        "name": "tags",
+                        "description": null,
+                        "args": [],
+                        "type": {
+                            "kind": "LIST",
+                            "ofType": { "kind": "NON_NULL", "ofType": { "kind": "NAMED", "name": "String" } }
+                        }
+                    },
+                    {
+                        "name": "friends",
+                        "description": null,
+                        "args": [{
+                            "name": "first",
+                            "description": null,
+                            "type": { "kind": "NON_NULL", "ofType": { "kind": "NAMED", "name": "Int" } }
+                        }],
+                        "type": {
+                            "kind": "NON_NULL",
+                            "ofType": {
+                                "kind": "LIST",
+                                "ofType": { "kind": "NON_NULL", "ofType": { "kind": "NAMED", "name": "User" } }
+                            }
+                        }
+                    }
+                ],
+                "inputFields": [],
+                "enumValues": [],
+                "possibleTypes": []
+            }]
+        });
+        assert_eq!(expected, serde_json::to_value(&ir).unwrap());
+    }
+
+    #[test]
+    fn it_folds_extensions_and_lists_union_members() {
+        let ir = schema_ir(
+            "
+            type User { id: String! }
+            type Bot { id: String! }
+            union Actor = User | Bot
+            type Query { me: Actor }
+            extend type Query { users: [User!]! }
+            ",
+        )
+        .unwrap();
+        let actor = ir.types.iter().find(|ty| ty.name == "Actor").unwrap();
+        assert_eq!(TypeKindIR::Union, actor.kind);
+        assert_eq!(vec!["User", "Bot"], actor.possible_types);
+
+        let queries = ir
+            .types
+            .iter()
+            .filter(|ty| ty.name == "Query")
+            .collect::<Vec<_>>();
+        assert_eq!(1, queries.len());
+        assert_eq!(
+            vec!["me", "users"],
+            queries[0]
+                .fields
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_reads_root_types_from_the_schema_definition() {
+        let ir = schema_ir("type Query { me: String }").unwrap();
+        assert_eq!(Some("Query"), ir.root_type(RootOperation::Query));
+        assert_eq!(None, ir.root_type(RootOperation::Mutation));
+
+        let ir = schema_ir(
+            "
+            schema { query: RootQuery mutation: RootMutation }
+            type Query { me: String }
+            type RootQuery { me: String }
+            type RootMutation { ban: String }
+            ",
+        )
+        .unwrap();
+        assert_eq!(Some("RootQuery"), ir.root_type(RootOperation::Query));
+        assert_eq!(Some("RootMutation"), ir.root_type(RootOperation::Mutation));
+        assert!(!ir.is_root_type("Query"));
+    }
+}
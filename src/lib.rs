@@ -1,6 +1,17 @@
 mod codegen;
+mod error;
+mod ir;
+mod resolvers;
 
 pub use codegen::*;
+pub use error::*;
+pub use ir::*;
+pub use resolvers::*;
+
+#[cfg(feature = "cli-diagnostics")]
+mod report;
+#[cfg(feature = "cli-diagnostics")]
+pub use report::report;
 
 #[cfg(feature = "node")]
 #[macro_use]
@@ -10,3 +21,6 @@ mod node;
 
 #[cfg(feature = "node")]
 pub use node::*;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
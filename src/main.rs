@@ -1,28 +1,578 @@
 #[cfg(not(feature = "node"))]
 fn main() {
+    use std::collections::HashMap;
     use std::fs::{self};
-    use tsgql::{generate_schema, parse_ts};
-    let filepath = std::env::args().nth(2).unwrap();
-    let outpath = std::env::args()
-        .nth(3)
+    use tsgql::{generate_schema_from_source_with_options, CodeGenOptions, TsParseConfig};
+    let mut args = std::env::args().collect::<Vec<String>>();
+    // `--format json` writes the schema IR instead of SDL
+    let format = match take_format(&mut args) {
+        Ok(format) => format,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    // `--options` reads `CodeGenOptions` from a JSON file
+    let options = match take_flag(&mut args, "--options") {
+        Ok(Some(path)) => read_options(&path),
+        Ok(None) => Ok(CodeGenOptions::default()),
+        Err(e) => Err(e),
+    };
+    let options = match options {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    // `--check` compares the schema to `outpath` instead of writing it, for CI
+    let (flags, args): (Vec<String>, Vec<String>) =
+        args.into_iter().partition(|arg| arg == "--check");
+    let check = !flags.is_empty();
+    let filepath = match args.get(2) {
+        Some(filepath) => filepath.clone(),
+        None => {
+            eprintln!(
+                "Usage: {} <command> <file.ts> [outpath] [manifest.json] [--check] [--format sdl|json] [--options options.json]",
+                args.first().map(String::as_str).unwrap_or("tsgql")
+            );
+            std::process::exit(1);
+        }
+    };
+    let outpath = args
+        .get(3)
+        .cloned()
         .unwrap_or_else(|| "./generated.schema".into());
 
     println!("filepath={}, outpath={}", filepath, outpath);
 
-    let code = fs::read_to_string(filepath).expect("failed to read file");
-    let prog = parse_ts(
-        code.as_str(),
-        "{
-            \"syntax\": \"typescript\",
-            \"tsx\": true,
-            \"decorators\": false,
-            \"dynamicImport\": false
-      }",
-    )
-    .unwrap();
+    let code = fs::read_to_string(&filepath).expect("failed to read file");
 
-    // generate_schema(prog)
+    // The manifest is an optional JSON file mapping type names to their kind
+    let manifest = match args.get(4).map(|path| read_manifest(path)) {
+        Some(Ok(manifest)) => manifest,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        None => HashMap::new(),
+    };
+
+    let schema = match generate_schema_from_source_with_options(
+        &code,
+        manifest,
+        &TsParseConfig::default(),
+        options,
+    ) {
+        Ok(schema) => schema,
+        // Print the offending Typescript underlined instead of the bare error
+        #[cfg(feature = "cli-diagnostics")]
+        Err(e) => {
+            eprintln!("{:?}", tsgql::report(e, &filepath, &code));
+            std::process::exit(1);
+        }
+        #[cfg(not(feature = "cli-diagnostics"))]
+        Err(e) => panic!("{:?}", e),
+    };
+    let schema = match render_schema(schema, format) {
+        Ok(schema) => schema,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    if check {
+        match check_schema(&outpath, &schema) {
+            Ok(()) => println!("{} is up to date", outpath),
+            Err(diff) => {
+                eprint!("{}", diff);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    fs::write(outpath, schema).expect("failed to write schema");
 }
 
 #[cfg(feature = "node")]
 fn main() {}
+
+/// What the CLI writes to `outpath`
+#[cfg(any(test, not(feature = "node")))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    Sdl,
+    /// The `SchemaIR` of the schema as pretty printed JSON
+    Json,
+}
+
+/// Removes `--format <sdl|json>` or `--format=<sdl|json>` from `args`, defaulting to SDL
+#[cfg(any(test, not(feature = "node")))]
+fn take_format(args: &mut Vec<String>) -> Result<OutputFormat, String> {
+    match take_flag(args, "--format")?.as_deref() {
+        None | Some("sdl") => Ok(OutputFormat::Sdl),
+        Some("json") => Ok(OutputFormat::Json),
+        Some(value) => Err(format!(
+            "Unknown --format `{}`, expected `sdl` or `json`",
+            value
+        )),
+    }
+}
+
+/// Removes `<flag> <value>` or `<flag>=<value>` from `args`, returning the value
+#[cfg(any(test, not(feature = "node")))]
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    let prefix = format!("{}=", flag);
+    let idx = match args
+        .iter()
+        .position(|arg| arg == flag || arg.starts_with(&prefix))
+    {
+        Some(idx) => idx,
+        None => return Ok(None),
+    };
+    let arg = args.remove(idx);
+    match arg.strip_prefix(&prefix) {
+        Some(value) => Ok(Some(value.to_string())),
+        None if idx < args.len() => Ok(Some(args.remove(idx))),
+        None => Err(format!("{} expects a value", flag)),
+    }
+}
+
+/// Reads the `CodeGenOptions` of a JSON file, in the camelCase of the node binding.
+/// Missing options keep their default
+#[cfg(any(test, not(feature = "node")))]
+fn read_options(path: &str) -> Result<tsgql::CodeGenOptions, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read options {}: {}", path, e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Failed to parse options {}: {}", path, e))
+}
+
+/// Reads a manifest file, a JSON object mapping type names to their kind or to an entry
+/// with per-type settings, like the manifest of the Node binding
+#[cfg(any(test, not(feature = "node")))]
+fn read_manifest(
+    path: &str,
+) -> Result<std::collections::HashMap<String, tsgql::ManifestEntry>, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read manifest {}: {}", path, e))?;
+    let raw: std::collections::HashMap<String, tsgql::ManifestValue> =
+        serde_json::from_str(&raw)
+            .map_err(|e| format!("Failed to parse manifest {}: {}", path, e))?;
+    raw.into_iter()
+        .map(|(name, value)| match value.into_entry(&name) {
+            Ok(entry) => Ok((name, entry)),
+            Err(e) => Err(e.to_string()),
+        })
+        .collect()
+}
+
+/// Returns the generated SDL as written in `format`
+#[cfg(any(test, not(feature = "node")))]
+fn render_schema(sdl: String, format: OutputFormat) -> anyhow::Result<String> {
+    match format {
+        OutputFormat::Sdl => Ok(sdl),
+        OutputFormat::Json => {
+            let ir = tsgql::schema_ir(&sdl)?;
+            Ok(serde_json::to_string_pretty(&ir)? + "\n")
+        }
+    }
+}
+
+/// Compares `schema` to the contents of `outpath` without writing it, erroring with a
+/// unified diff when they differ. A missing file is compared as empty
+#[cfg(any(test, not(feature = "node")))]
+fn check_schema(outpath: &str, schema: &str) -> Result<(), String> {
+    let existing = std::fs::read_to_string(outpath).unwrap_or_default();
+    if existing == schema {
+        return Ok(());
+    }
+    Err(format!(
+        "--- {}\n+++ {} (generated)\n{}",
+        outpath,
+        outpath,
+        line_diff(&existing, schema)
+    ))
+}
+
+/// Lines of a diff, `Keep` is in both files
+#[cfg(any(test, not(feature = "node")))]
+enum DiffLine<'a> {
+    Keep(&'a str),
+    Remove(&'a str),
+    Add(&'a str),
+}
+
+/// Number of unchanged lines shown around each change
+#[cfg(any(test, not(feature = "node")))]
+const DIFF_CONTEXT: usize = 3;
+
+/// Diffs `old` and `new` line by line with Myers' algorithm, and returns the changes as
+/// unified diff hunks
+#[cfg(any(test, not(feature = "node")))]
+fn line_diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    diff_lines(&old, &new, &mut lines);
+
+    // Group the changes into hunks, merging those separated by less than twice the context
+    let mut out = String::new();
+    let mut start = 0;
+    while let Some(first) = (start..lines.len()).find(|&k| !matches!(lines[k], DiffLine::Keep(_))) {
+        let mut end = first;
+        let mut k = first;
+        while k < lines.len() {
+            if !matches!(lines[k], DiffLine::Keep(_)) {
+                end = k;
+            } else if k - end > 2 * DIFF_CONTEXT {
+                break;
+            }
+            k += 1;
+        }
+        let from = first.saturating_sub(DIFF_CONTEXT).max(start);
+        let to = (end + DIFF_CONTEXT + 1).min(lines.len());
+
+        // Line numbers of the hunk in each file, counted from the lines before it
+        let old_start = lines[..from]
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Add(_)))
+            .count();
+        let new_start = lines[..from]
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Remove(_)))
+            .count();
+        let hunk = &lines[from..to];
+        let old_len = hunk
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Add(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Remove(_)))
+            .count();
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_len),
+            hunk_range(new_start, new_len)
+        ));
+        for line in hunk {
+            let (prefix, text) = match line {
+                DiffLine::Keep(text) => (' ', text),
+                DiffLine::Remove(text) => ('-', text),
+                DiffLine::Add(text) => ('+', text),
+            };
+            out.push_str(&format!("{}{}\n", prefix, text));
+        }
+        start = to;
+    }
+    out
+}
+
+/// Appends a shortest edit script from `old` to `new` to `lines`. Both are split at the
+/// middle snake of the script and diffed recursively, which takes linear space
+#[cfg(any(test, not(feature = "node")))]
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str], lines: &mut Vec<DiffLine<'a>>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    lines.extend(old[..prefix].iter().map(|line| DiffLine::Keep(line)));
+
+    let old_rest = &old[prefix..old.len() - suffix];
+    let new_rest = &new[prefix..new.len() - suffix];
+    if old_rest.is_empty() || new_rest.is_empty() {
+        lines.extend(old_rest.iter().map(|line| DiffLine::Remove(line)));
+        lines.extend(new_rest.iter().map(|line| DiffLine::Add(line)));
+    } else {
+        let (x, y, u, v) = middle_snake(old_rest, new_rest);
+        diff_lines(&old_rest[..x], &new_rest[..y], lines);
+        lines.extend(old_rest[x..u].iter().map(|line| DiffLine::Keep(line)));
+        diff_lines(&old_rest[u..], &new_rest[v..], lines);
+    }
+
+    lines.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| DiffLine::Keep(line)),
+    );
+}
+
+/// Returns the start `(x, y)` and end `(u, v)` of the middle snake of a shortest edit
+/// script from `old` to `new`, by searching from both ends until the paths overlap
+#[cfg(any(test, not(feature = "node")))]
+fn middle_snake(old: &[&str], new: &[&str]) -> (usize, usize, usize, usize) {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let delta = n - m;
+    let max = (n + m + 1) / 2;
+    let offset = max + 1;
+    // Furthest `x` reached on each diagonal `x - y`, from the start and from the end
+    let mut forward = vec![0; 2 * max as usize + 3];
+    let mut backward = forward.clone();
+    let furthest = |v: &[isize], k: isize, d: isize| {
+        let i = (k + offset) as usize;
+        if k == -d || (k != d && v[i - 1] < v[i + 1]) {
+            v[i + 1]
+        } else {
+            v[i - 1] + 1
+        }
+    };
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let x0 = furthest(&forward, k, d);
+            let y0 = x0 - k;
+            let (mut x, mut y) = (x0, y0);
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[(k + offset) as usize] = x;
+            let c = delta - k;
+            if delta % 2 != 0 && c.abs() < d && x + backward[(c + offset) as usize] >= n {
+                return (x0 as usize, y0 as usize, x as usize, y as usize);
+            }
+        }
+        for c in (-d..=d).step_by(2) {
+            let x0 = furthest(&backward, c, d);
+            let y0 = x0 - c;
+            let (mut x, mut y) = (x0, y0);
+            while x < n && y < m && old[(n - x - 1) as usize] == new[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[(c + offset) as usize] = x;
+            let k = delta - c;
+            if delta % 2 == 0 && k.abs() <= d && x + forward[(k + offset) as usize] >= n {
+                return (
+                    (n - x) as usize,
+                    (m - y) as usize,
+                    (n - x0) as usize,
+                    (m - y0) as usize,
+                );
+            }
+        }
+    }
+    unreachable!("paths from both ends overlap within (n + m) / 2 edits")
+}
+
+/// Formats the range of a hunk in one file, an empty range starts at the line before it
+#[cfg(any(test, not(feature = "node")))]
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn it_diffs_lines() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+        assert_eq!(
+            indoc! {"
+            @@ -1,5 +1,5 @@
+             a
+            -b
+            +B
+             c
+             d
+             e
+            @@ -9,3 +9,4 @@
+             i
+             j
+             k
+            +l
+            "},
+            line_diff(old, new)
+        );
+        assert_eq!("@@ -0,0 +1,2 @@\n+a\n+b\n", line_diff("", "a\nb\n"));
+        assert_eq!("", line_diff("a\n", "a\n"));
+    }
+
+    #[test]
+    fn it_checks_the_schema_without_writing() {
+        let outpath =
+            std::env::temp_dir().join(format!("tsgql-check-{}.graphql", std::process::id()));
+        let outpath = outpath.to_str().unwrap();
+        std::fs::write(outpath, "type User {\n  id: Int!\n}\n").unwrap();
+
+        assert_eq!(
+            Ok(()),
+            check_schema(outpath, "type User {\n  id: Int!\n}\n")
+        );
+        let err = check_schema(outpath, "type User {\n  id: ID!\n}\n").unwrap_err();
+        assert_eq!(
+            format!(
+                "--- {0}\n+++ {0} (generated)\n@@ -1,3 +1,3 @@\n type User {{\n-  id: Int!\n+  id: ID!\n }}\n",
+                outpath
+            ),
+            err
+        );
+        // The existing schema is left untouched
+        assert_eq!(
+            "type User {\n  id: Int!\n}\n",
+            std::fs::read_to_string(outpath).unwrap()
+        );
+
+        std::fs::remove_file(outpath).unwrap();
+        assert!(check_schema(outpath, "type User {\n  id: Int!\n}\n").is_err());
+    }
+
+    #[test]
+    fn it_reports_invalid_manifests() {
+        let path = std::env::temp_dir().join(format!("tsgql-manifest-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, "{ \"User\": 0 }").unwrap();
+        assert_eq!(
+            Some(&tsgql::GraphQLKind::Object.into()),
+            read_manifest(path).unwrap().get("User")
+        );
+        std::fs::write(
+            path,
+            "{ \"Query\": { \"kind\": \"object\", \"root\": \"query\", \"extends\": [\"Node\"] } }",
+        )
+        .unwrap();
+        assert_eq!(
+            Some(&tsgql::ManifestEntry {
+                root: Some(tsgql::RootOperation::Query),
+                extends: vec!["Node".to_string()],
+                ..tsgql::GraphQLKind::Object.into()
+            }),
+            read_manifest(path).unwrap().get("Query")
+        );
+        std::fs::write(path, "{ \"Query\": { \"kind\": 0, \"root\": \"query!\" } }").unwrap();
+        assert_eq!(
+            Err("Invalid root operation for Query: query!".to_string()),
+            read_manifest(path)
+        );
+        std::fs::write(path, "{ \"User\": 9 }").unwrap();
+        assert_eq!(
+            Err("Invalid manifest kind for User: 9".to_string()),
+            read_manifest(path)
+        );
+
+        std::fs::remove_file(path).unwrap();
+        assert!(read_manifest(path)
+            .unwrap_err()
+            .starts_with("Failed to read manifest"));
+    }
+
+    #[test]
+    fn it_reads_codegen_options() {
+        let mut args = vec!["tsgql", "in.ts", "--options", "opts.json", "--check"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            Ok(Some("opts.json".to_string())),
+            take_flag(&mut args, "--options")
+        );
+        assert_eq!(vec!["tsgql", "in.ts", "--check"], args);
+        assert_eq!(Ok(None), take_flag(&mut args, "--options"));
+        assert_eq!(
+            Err("--options expects a value".to_string()),
+            take_flag(&mut vec!["--options".to_string()], "--options")
+        );
+
+        let path = std::env::temp_dir().join(format!("tsgql-options-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "{ \"typePrefix\": \"Foo_\" }").unwrap();
+        let options = read_options(path).unwrap();
+        assert_eq!(Some("Foo_".to_string()), options.type_prefix);
+        assert_eq!(tsgql::CodeGenOptions::default().strict, options.strict);
+
+        std::fs::write(path, "{ \"typePrefix\": 1 }").unwrap();
+        assert!(read_options(path)
+            .unwrap_err()
+            .starts_with("Failed to parse options"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn it_takes_the_output_format() {
+        let mut args = vec!["tsgql", "--format", "json", "in.ts", "--check"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        assert_eq!(Ok(OutputFormat::Json), take_format(&mut args));
+        assert_eq!(vec!["tsgql", "in.ts", "--check"], args);
+
+        let mut args = vec!["tsgql".to_string(), "--format=sdl".to_string()];
+        assert_eq!(Ok(OutputFormat::Sdl), take_format(&mut args));
+        assert_eq!(vec!["tsgql"], args);
+        assert_eq!(Ok(OutputFormat::Sdl), take_format(&mut args));
+
+        assert_eq!(
+            Err("Unknown --format `yaml`, expected `sdl` or `json`".to_string()),
+            take_format(&mut vec!["--format".to_string(), "yaml".to_string()])
+        );
+        assert_eq!(
+            Err("--format expects a value".to_string()),
+            take_format(&mut vec!["--format".to_string()])
+        );
+        // Only `--format` itself is taken, not flags it prefixes
+        let mut args = vec!["--formats".to_string(), "json".to_string()];
+        assert_eq!(Ok(OutputFormat::Sdl), take_format(&mut args));
+        assert_eq!(vec!["--formats", "json"], args);
+    }
+
+    #[test]
+    fn it_writes_the_schema_ir_as_json() {
+        let code = "type User = { id: string; friends(first: number): Promise<User[]>; }";
+        let manifest = vec![("User".to_string(), tsgql::GraphQLKind::Object)]
+            .into_iter()
+            .collect();
+        let sdl =
+            tsgql::generate_schema_from_source(code, manifest, &tsgql::TsParseConfig::default())
+                .unwrap();
+        let json = render_schema(sdl, OutputFormat::Json).unwrap();
+        let non_null = |of_type| serde_json::json!({ "kind": "NON_NULL", "ofType": of_type });
+        let named = |name| serde_json::json!({ "kind": "NAMED", "name": name });
+        assert_eq!(
+            serde_json::json!({
+                "queryType": null,
+                "mutationType": null,
+                "subscriptionType": null,
+                "types": [{
+                    "kind": "OBJECT",
+                    "name": "User",
+                    "description": null,
+                    "interfaces": [],
+                    "fields": [
+                        {
+                            "name": "id",
+                            "description": null,
+                            "args": [],
+                            "type": non_null(named("String")),
+                        },
+                        {
+                            "name": "friends",
+                            "description": null,
+                            "args": [{
+                                "name": "first",
+                                "description": null,
+                                "type": non_null(named("Int")),
+                            }],
+                            "type": non_null(serde_json::json!({
+                                "kind": "LIST",
+                                "ofType": non_null(named("User")),
+                            })),
+                        },
+                    ],
+                    "inputFields": [],
+                    "enumValues": [],
+                    "possibleTypes": [],
+                }]
+            }),
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        );
+        assert!(json.ends_with("}\n"));
+    }
+}
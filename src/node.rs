@@ -1,8 +1,24 @@
-use std::{collections::HashMap, fs};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fs,
+    sync::{Arc, Mutex},
+};
 
-use napi::{CallContext, Error, JsNumber, JsObject, JsString, Result};
+use napi::{
+    CallContext, Env, Error, JsBoolean, JsFunction, JsNumber, JsObject, JsString, JsUnknown,
+    Result, ValueType,
+};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use swc::config::ParseOptions;
 
-use crate::{generate_schema, parse_ts, GraphQLKind};
+use crate::{
+    generate_schema_with_shared_manifest, operation_index, parse_ts_with_parse_options, schema_ir,
+    type_index, validate_schema, GenerateOptions, ManifestEntry, ManifestValue, OperationInfo,
+    SchemaMeta,
+};
 
 #[cfg(all(
     any(windows, unix),
@@ -16,31 +32,186 @@ static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[module_exports]
 fn init(mut exports: JsObject) -> Result<()> {
     exports.create_named_method("generateSchema", generate)?;
+    exports.create_named_method("generateSchemaWithMeta", generate_with_meta)?;
+    exports.create_named_method("registerManifest", register_manifest)?;
+    exports.create_named_method("unregisterManifest", unregister_manifest)?;
+    exports.create_named_method("generateSchemaWithManifest", generate_with_manifest)?;
     Ok(())
 }
 
-#[js_function(4)]
+/// Manifests registered with `registerManifest`, keyed by the id returned to JS
+#[derive(Default)]
+struct Manifests {
+    next_id: u32,
+    /// Shared so generating with a manifest only holds the lock to clone the `Arc`
+    entries: HashMap<u32, Arc<HashMap<String, ManifestEntry>>>,
+}
+
+static MANIFESTS: Lazy<Mutex<Manifests>> = Lazy::new(Mutex::default);
+
+/// Reads an argument passed either as a plain JS value or as its JSON string, the
+/// latter is still accepted for callers that serialize their options
+fn from_js_arg<T: DeserializeOwned>(env: &Env, value: JsUnknown) -> Result<T> {
+    match value.get_type()? {
+        ValueType::String => {
+            let value = JsString::try_from(value)?.into_utf8()?;
+            Ok(serde_json::from_str(value.as_str()?)?)
+        }
+        _ => env.from_js_value(value),
+    }
+}
+
+#[js_function(5)]
 fn generate(ctx: CallContext) -> Result<JsString> {
-    let code = ctx.get::<JsString>(0)?.into_utf8()?;
-    let manifest = ctx.get::<JsString>(1)?.into_utf8()?;
-    let opts = ctx.get::<JsString>(2)?.into_utf8()?;
+    let output = generate_sdl(&ctx, Arc::new(read_manifest(&ctx)?))?;
+    ctx.env.create_string(&output)
+}
+
+/// Parses the manifest in the 1st argument once and keeps it, returning the id
+/// `generateSchemaWithManifest` takes in place of the manifest
+#[js_function(1)]
+fn register_manifest(ctx: CallContext) -> Result<JsNumber> {
+    let manifest = parse_manifest(from_js_arg(ctx.env, ctx.get::<JsUnknown>(0)?)?)?;
+    let mut manifests = MANIFESTS.lock().unwrap();
+    let id = manifests.next_id;
+    manifests.next_id += 1;
+    manifests.entries.insert(id, Arc::new(manifest));
+    ctx.env.create_uint32(id)
+}
+
+/// Drops the manifest registered under the id in the 1st argument, returning whether
+/// it was registered
+#[js_function(1)]
+fn unregister_manifest(ctx: CallContext) -> Result<JsBoolean> {
+    let id = ctx.get::<JsNumber>(0)?.get_uint32()?;
+    let removed = MANIFESTS.lock().unwrap().entries.remove(&id).is_some();
+    ctx.env.get_boolean(removed)
+}
+
+/// Like `generateSchema`, but the 2nd argument is the id of a manifest registered with
+/// `registerManifest` instead of the manifest itself
+#[js_function(5)]
+fn generate_with_manifest(ctx: CallContext) -> Result<JsString> {
+    let id = ctx.get::<JsNumber>(1)?.get_uint32()?;
+    // The guard is dropped at the end of this statement, before generating
+    let manifest = match MANIFESTS.lock().unwrap().entries.get(&id) {
+        Some(manifest) => Arc::clone(manifest),
+        None => {
+            return Err(Error::new(
+                napi::Status::InvalidArg,
+                format!("Unknown manifest id: {}", id),
+            ))
+        }
+    };
+    let output = generate_sdl(&ctx, manifest)?;
+    ctx.env.create_string(&output)
+}
+
+/// Output of `generateSchemaWithMeta`
+#[derive(Serialize)]
+struct GeneratedSchema {
+    schema: String,
+    meta: SchemaMeta,
+    operations: Vec<OperationInfo>,
+}
+
+/// Like `generateSchema`, but returns `{ schema, meta, operations }` where `meta`
+/// describes the generated types and `operations` the fields of the root types
+#[js_function(5)]
+fn generate_with_meta(ctx: CallContext) -> Result<JsUnknown> {
+    let schema = generate_sdl(&ctx, Arc::new(read_manifest(&ctx)?))?;
+    let ir =
+        schema_ir(&schema).map_err(|e| Error::new(napi::Status::Unknown, format!("{:?}", e)))?;
+    ctx.env.to_js_value(&GeneratedSchema {
+        operations: operation_index(&ir),
+        meta: type_index(&ir),
+        schema,
+    })
+}
+
+/// Reads the manifest passed in the 2nd argument
+fn read_manifest(ctx: &CallContext) -> Result<HashMap<String, ManifestEntry>> {
+    parse_manifest(from_js_arg(ctx.env, ctx.get::<JsUnknown>(1)?)?)
+}
 
-    let manifest_raw: HashMap<String, u8> = serde_json::from_str(manifest.as_str()?)?;
+fn parse_manifest(
+    manifest_raw: HashMap<String, ManifestValue>,
+) -> Result<HashMap<String, ManifestEntry>> {
+    let mut manifest: HashMap<String, ManifestEntry> = HashMap::with_capacity(manifest_raw.len());
+    for (name, value) in manifest_raw {
+        let entry = value
+            .into_entry(&name)
+            .map_err(|e| Error::new(napi::Status::InvalidArg, e.to_string()))?;
+        manifest.insert(name, entry);
+    }
+    Ok(manifest)
+}
 
-    let mut manifest: HashMap<String, GraphQLKind> = HashMap::with_capacity(manifest_raw.len());
-    manifest_raw.into_iter().for_each(|(s, val)| {
-        manifest.insert(s, GraphQLKind::from_u8(val).unwrap());
-    });
+/// Reads the arguments shared by the exported functions besides the manifest, and
+/// generates the SDL
+fn generate_sdl(
+    ctx: &CallContext,
+    manifest: Arc<HashMap<String, ManifestEntry>>,
+) -> Result<String> {
+    let code = ctx.get::<JsString>(0)?.into_utf8()?;
+    let opts: ParseOptions = from_js_arg(ctx.env, ctx.get::<JsUnknown>(2)?)?;
+    let gen_opts: GenerateOptions = if ctx.length > 3 {
+        let gen_opts = ctx.get::<JsUnknown>(3)?;
+        match gen_opts.get_type()? {
+            ValueType::Undefined | ValueType::Null => GenerateOptions::default(),
+            _ => from_js_arg(ctx.env, gen_opts)?,
+        }
+    } else {
+        GenerateOptions::default()
+    };
 
-    let prog = match parse_ts(code.as_str()?, opts.as_str()?) {
+    let (prog, comments) = match parse_ts_with_parse_options(code.as_str()?, opts) {
         Ok(p) => p,
         Err(e) => return Err(Error::new(napi::Status::Unknown, format!("{:?}", e))),
     };
 
-    let output = match generate_schema(prog.module().unwrap(), manifest) {
+    // Optional 5th argument, called with the name of each type as it is generated
+    let on_type = if ctx.length > 4 {
+        let on_type = ctx.get::<JsUnknown>(4)?;
+        match on_type.get_type()? {
+            ValueType::Function => Some(JsFunction::try_from(on_type)?),
+            _ => None,
+        }
+    } else {
+        None
+    };
+    // The callback can't fail generation, so its first error is returned afterwards
+    let mut callback_error = None;
+    let output = generate_schema_with_shared_manifest(
+        prog.module().unwrap(),
+        comments,
+        manifest,
+        gen_opts.codegen,
+        |name| {
+            if let (Some(on_type), None) = (&on_type, &callback_error) {
+                if let Err(e) = ctx
+                    .env
+                    .create_string(name)
+                    .and_then(|name| on_type.call(None, &[name]))
+                {
+                    callback_error = Some(e);
+                }
+            }
+        },
+    );
+    if let Some(e) = callback_error {
+        return Err(e);
+    }
+    let output = match output {
         Ok(output) => output,
         Err(e) => return Err(Error::new(napi::Status::Unknown, format!("{:?}", e))),
     };
 
-    ctx.env.create_string(&output)
+    if gen_opts.validate {
+        if let Err(e) = validate_schema(&output) {
+            return Err(Error::new(napi::Status::Unknown, format!("{:?}", e)));
+        }
+    }
+
+    Ok(output)
 }
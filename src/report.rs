@@ -0,0 +1,82 @@
+use std::{error::Error, fmt};
+
+use miette::{Diagnostic, LabeledSpan, NamedSource, Report, SourceCode};
+
+use crate::SourceError;
+
+/// A generation error with the snippet of Typescript it points at
+#[derive(Debug)]
+struct SourceDiagnostic {
+    message: String,
+    source: NamedSource,
+    span: (usize, usize),
+}
+
+impl fmt::Display for SourceDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for SourceDiagnostic {}
+
+impl Diagnostic for SourceDiagnostic {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(std::iter::once(LabeledSpan::new(
+            Some("here".to_string()),
+            self.span.0,
+            self.span.1,
+        ))))
+    }
+}
+
+/// Turns an error returned while generating the schema of `code`, read from the file
+/// `name`, into a report that underlines the offending Typescript when the error
+/// points at a node
+pub fn report(error: anyhow::Error, name: &str, code: &str) -> Report {
+    match error.downcast::<SourceError>() {
+        Ok(error) => {
+            let range = error.range();
+            Report::new(SourceDiagnostic {
+                message: error.message().to_string(),
+                source: NamedSource::new(name, code.to_string()),
+                span: (range.start, range.len()),
+            })
+        }
+        Err(error) => miette::miette!("{:#}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use miette::{GraphicalReportHandler, GraphicalTheme};
+
+    use super::*;
+    use crate::{generate_schema_from_source, GraphQLKind, TsParseConfig};
+
+    #[test]
+    fn it_underlines_the_failing_field() {
+        let code = "type User = {\n  id: string;\n  payload: never;\n}\n";
+        let mut manifest = HashMap::new();
+        manifest.insert("User".to_string(), GraphQLKind::Object);
+        let error =
+            generate_schema_from_source(code, manifest, &TsParseConfig::default()).unwrap_err();
+
+        let report = report(error, "user.ts", code);
+        let mut rendered = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut rendered, report.as_ref())
+            .unwrap();
+
+        assert!(rendered.contains("user.ts"));
+        assert!(rendered.contains("payload: never;"));
+        assert!(rendered
+            .contains("Field `payload` resolves to `never`, which has no GraphQL representation"));
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{SchemaIR, TypeIR, TypeKindIR, TypeRefIR};
+
+/// Generates a Typescript `Resolvers` type for the schema described by `ir`, with a
+/// resolver signature for every field of the root types and every field with args of
+/// the other objects, e.g.
+///
+/// ```ts
+/// export type User = {
+///   id: string;
+/// };
+///
+/// export type Resolvers<TContext = unknown> = {
+///   Query: {
+///     findUser: (args: { id: string }, ctx: TContext) => MaybePromise<User | null>;
+///   };
+/// };
+/// ```
+///
+/// Every type but the scalars and root types is declared under its GraphQL name, so the
+/// output doesn't depend on the Typescript it was generated from, which may name types
+/// differently or not at all, like the inputs synthesized for args. Custom scalars
+/// become `unknown`
+pub fn generate_resolver_types(ir: &SchemaIR) -> String {
+    let scalars = ir
+        .types
+        .iter()
+        .filter(|ty| ty.kind == TypeKindIR::Scalar)
+        .map(|ty| ty.name.as_str())
+        .collect::<Vec<&str>>();
+
+    let mut out = String::from("type MaybePromise<T> = T | Promise<T>;\n\n");
+    for ty in ir
+        .types
+        .iter()
+        .filter(|ty| ty.kind != TypeKindIR::Scalar && !ir.is_root_type(&ty.name))
+    {
+        out.push_str(&declaration(ty, &scalars));
+        out.push('\n');
+    }
+
+    out.push_str("export type Resolvers<TContext = unknown> = {\n");
+    for ty in ir.types.iter().filter(|ty| ty.kind == TypeKindIR::Object) {
+        let is_root = ir.is_root_type(&ty.name);
+        let fields = ty
+            .fields
+            .iter()
+            .filter(|field| is_root || !field.args.is_empty())
+            .collect::<Vec<_>>();
+        if fields.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("  {}: {{\n", ty.name));
+        for field in fields {
+            let args = field
+                .args
+                .iter()
+                .map(|arg| match &arg.ty {
+                    TypeRefIR::NonNull { of_type } => {
+                        format!("{}: {}", arg.name, ts_type(of_type, &scalars))
+                    }
+                    ty => format!("{}?: {} | null", arg.name, ts_type(ty, &scalars)),
+                })
+                .collect::<Vec<String>>();
+            let args = if args.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", args.join("; "))
+            };
+            out.push_str(&format!(
+                "    {}: (args: {}, ctx: TContext) => MaybePromise<{}>;\n",
+                field.name,
+                args,
+                nullable_ts_type(&field.ty, &scalars)
+            ));
+        }
+        out.push_str("  };\n");
+    }
+    out.push_str("};\n");
+    out
+}
+
+/// Returns the Typescript declaration of an enum, union, input, object or interface
+fn declaration(ty: &TypeIR, scalars: &[&str]) -> String {
+    let members = match ty.kind {
+        TypeKindIR::Enum => ty
+            .enum_values
+            .iter()
+            .map(|value| format!("'{}'", value.name))
+            .collect::<Vec<String>>(),
+        TypeKindIR::Union => ty.possible_types.clone(),
+        TypeKindIR::InputObject => {
+            let fields = ty.input_fields.iter().map(|field| match &field.ty {
+                TypeRefIR::NonNull { of_type } => {
+                    format!("  {}: {};\n", field.name, ts_type(of_type, scalars))
+                }
+                of_type => format!("  {}?: {} | null;\n", field.name, ts_type(of_type, scalars)),
+            });
+            return format!(
+                "export type {} = {{\n{}}};\n",
+                ty.name,
+                fields.collect::<String>()
+            );
+        }
+        _ => {
+            let fields = ty.fields.iter().map(|field| {
+                format!(
+                    "  {}: {};\n",
+                    field.name,
+                    nullable_ts_type(&field.ty, scalars)
+                )
+            });
+            return format!(
+                "export type {} = {{\n{}}};\n",
+                ty.name,
+                fields.collect::<String>()
+            );
+        }
+    };
+    format!("export type {} = {};\n", ty.name, members.join(" | "))
+}
+
+/// Returns the Typescript type of `ty`, adding `| null` unless it is non-null
+fn nullable_ts_type(ty: &TypeRefIR, scalars: &[&str]) -> String {
+    match ty {
+        TypeRefIR::NonNull { of_type } => ts_type(of_type, scalars),
+        ty => format!("{} | null", ts_type(ty, scalars)),
+    }
+}
+
+/// Returns the Typescript type of a nullable `ty`, without the `| null`
+fn ts_type(ty: &TypeRefIR, scalars: &[&str]) -> String {
+    match ty {
+        TypeRefIR::NonNull { of_type } => ts_type(of_type, scalars),
+        TypeRefIR::List { of_type } => match &**of_type {
+            TypeRefIR::NonNull { of_type } => format!("{}[]", ts_type(of_type, scalars)),
+            of_type => format!("({} | null)[]", ts_type(of_type, scalars)),
+        },
+        TypeRefIR::Named { name } => match name.as_str() {
+            "String" | "ID" => "string".to_string(),
+            "Int" | "Float" => "number".to_string(),
+            "Boolean" => "boolean".to_string(),
+            name if scalars.contains(&name) => "unknown".to_string(),
+            name => name.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::{generate_schema_ir, parse_ts, GraphQLKind};
+
+    #[test]
+    fn it_generates_resolver_types() {
+        let prog = parse_ts(
+            "
+            type User = {
+                id: string;
+                role: 'admin' | 'member';
+                friends(first?: number): Promise<User[]>;
+            }
+            type UserFilter = {
+                name?: string;
+            }
+            type Query = {
+                findUser: (args: { id: string }) => Promise<User | null>;
+                users: (args: { filter?: UserFilter; ids: string[] }) => Promise<(User | null)[]>;
+                count: number;
+            }
+            ",
+            "{ \"syntax\": \"typescript\" }",
+        )
+        .unwrap();
+        let mut manifest = HashMap::new();
+        manifest.insert("User".to_string(), GraphQLKind::Object);
+        manifest.insert("UserFilter".to_string(), GraphQLKind::Input);
+        manifest.insert("Query".to_string(), GraphQLKind::Object);
+
+        let ir = generate_schema_ir(prog.module().unwrap(), manifest).unwrap();
+        assert_eq!(
+            indoc! { r#"
+            type MaybePromise<T> = T | Promise<T>;
+
+            export type UserRoleEnum = 'admin' | 'member';
+
+            export type User = {
+              id: string;
+              role: UserRoleEnum;
+              friends: User[];
+            };
+
+            export type UserFilter = {
+              name?: string | null;
+            };
+
+            export type Resolvers<TContext = unknown> = {
+              User: {
+                friends: (args: { first?: number | null }, ctx: TContext) => MaybePromise<User[]>;
+              };
+              Query: {
+                findUser: (args: { id: string }, ctx: TContext) => MaybePromise<User | null>;
+                users: (args: { filter?: UserFilter | null; ids: string[] }, ctx: TContext) => MaybePromise<(User | null)[]>;
+                count: (args: {}, ctx: TContext) => MaybePromise<number>;
+              };
+            };
+            "# },
+            generate_resolver_types(&ir)
+        );
+    }
+
+    #[test]
+    fn it_declares_synthesized_types_and_takes_roots_from_the_schema() {
+        let ir = crate::schema_ir(
+            "
+            schema { query: RootQuery }
+            type Post { title: String! }
+            union SearchResult = Post | Query
+            type Query { posts: [Post!]! }
+            input SearchInputFilter { text: String! limit: Int }
+            type RootQuery { search(filter: SearchInputFilter!): [SearchResult!]! }
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            indoc! { r#"
+            type MaybePromise<T> = T | Promise<T>;
+
+            export type Post = {
+              title: string;
+            };
+
+            export type SearchResult = Post | Query;
+
+            export type Query = {
+              posts: Post[];
+            };
+
+            export type SearchInputFilter = {
+              text: string;
+              limit?: number | null;
+            };
+
+            export type Resolvers<TContext = unknown> = {
+              RootQuery: {
+                search: (args: { filter: SearchInputFilter }, ctx: TContext) => MaybePromise<SearchResult[]>;
+              };
+            };
+            "# },
+            generate_resolver_types(&ir)
+        );
+    }
+}
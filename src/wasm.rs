@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    generate_schema_with_options, parse_ts_with_comments, validate_schema, GenerateOptions,
+    GraphQLKind,
+};
+
+/// Exported to JS as
+/// `generateSchema(code: string, manifestJson: string, optsJson: string, genOptsJson?: string): string`.
+///
+/// Mirrors the Node binding: `manifest_json` maps type names to their kind
+/// (`0` object, `1` input, `2` enum, `3` interface), `opts_json` holds swc's parse
+/// options and `gen_opts_json` the codegen options, like `{ "forceNullable": true }`.
+/// Errors are thrown as JS strings.
+#[wasm_bindgen(js_name = generateSchema)]
+pub fn generate_schema(
+    code: &str,
+    manifest_json: &str,
+    opts_json: &str,
+    gen_opts_json: Option<String>,
+) -> Result<String, JsValue> {
+    generate(code, manifest_json, opts_json, gen_opts_json.as_deref())
+        .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+}
+
+fn generate(
+    code: &str,
+    manifest_json: &str,
+    opts_json: &str,
+    gen_opts_json: Option<&str>,
+) -> anyhow::Result<String> {
+    let manifest_raw: HashMap<String, u8> = serde_json::from_str(manifest_json)?;
+
+    let mut manifest: HashMap<String, GraphQLKind> = HashMap::with_capacity(manifest_raw.len());
+    for (name, val) in manifest_raw {
+        let kind = GraphQLKind::from_u8(val)
+            .ok_or_else(|| anyhow::anyhow!("Invalid manifest kind for {}: {}", name, val))?;
+        manifest.insert(name, kind);
+    }
+    let gen_opts: GenerateOptions = match gen_opts_json {
+        Some(json) => serde_json::from_str(json)?,
+        None => GenerateOptions::default(),
+    };
+
+    let (prog, comments) = parse_ts_with_comments(code, opts_json)?;
+    let module = prog
+        .module()
+        .ok_or_else(|| anyhow::anyhow!("Expected a module, got a script"))?;
+
+    let output = generate_schema_with_options(module, comments, manifest, gen_opts.codegen)?;
+    if gen_opts.validate {
+        validate_schema(&output)?;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_generates_from_json_arguments() {
+        let out = generate_schema(
+            "type User = { id: string; }",
+            "{ \"User\": 0 }",
+            "{ \"syntax\": \"typescript\", \"tsx\": true }",
+            None,
+        )
+        .unwrap();
+        assert_eq!("type User {\n  id: String!\n}\n", out);
+    }
+
+    #[test]
+    fn it_reads_codegen_options() {
+        let out = generate(
+            "type User = { id: string; }",
+            "{ \"User\": 0 }",
+            "{ \"syntax\": \"typescript\" }",
+            Some("{ \"forceNullable\": true, \"validate\": true }"),
+        )
+        .unwrap();
+        assert_eq!("type User {\n  id: String\n}\n", out);
+    }
+
+    #[test]
+    fn it_rejects_unknown_manifest_kinds() {
+        let err =
+            generate("type User = { id: string; }", "{ \"User\": 9 }", "{}", None).unwrap_err();
+        assert_eq!("Invalid manifest kind for User: 9", err.to_string());
+    }
+}
@@ -0,0 +1,80 @@
+//! Runs the `tsgql` binary end to end. It is empty when built for Node, so these only
+//! run with `cargo test --no-default-features`
+#![cfg(not(feature = "node"))]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Returns a path in the temp dir unique to this process and `name`
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tsgql-cli-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn it_writes_the_schema_ir_as_json() {
+    let input = temp_path("input.ts");
+    let manifest = temp_path("manifest.json");
+    let outpath = temp_path("schema.json");
+    fs::write(
+        &input,
+        "
+        type User = { id: string; }
+        type Bot = { id: string; }
+        type Actor = User | Bot;
+        type Query = { me: Actor; }
+        /** @gqlExtend */
+        type Query = { users(): Promise<User[]>; }
+        ",
+    )
+    .unwrap();
+    fs::write(
+        &manifest,
+        r#"{ "User": 0, "Bot": 0, "Actor": 4, "Query": 0 }"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tsgql"))
+        .arg("generate")
+        .arg(&input)
+        .arg(&outpath)
+        .arg(&manifest)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&outpath).unwrap()).unwrap();
+    let types = json["types"].as_array().unwrap();
+    let find = |name: &str| {
+        types
+            .iter()
+            .find(|ty| ty["name"] == name)
+            .unwrap_or_else(|| panic!("{} is missing from {}", name, json))
+    };
+
+    assert_eq!("Query", json["queryType"]);
+    assert_eq!("UNION", find("Actor")["kind"]);
+    assert_eq!(
+        serde_json::json!(["User", "Bot"]),
+        find("Actor")["possibleTypes"]
+    );
+    // The fields of `extend type Query` are folded into `Query`
+    let fields = find("Query")["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["me", "users"], fields);
+
+    for path in &[input, manifest, outpath] {
+        fs::remove_file(path).unwrap();
+    }
+}